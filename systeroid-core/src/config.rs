@@ -1,10 +1,13 @@
 use crate::error::Result;
+use crate::sysctl::namespace::NamespaceTarget;
 use crate::sysctl::r#type::{DisplayType, OutputType};
 use crate::sysctl::section::Section;
+use crate::sysctl::validation::Rule;
 use ini::Ini;
 use owo_colors::colored::Color;
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::str::FromStr;
 
 /// Default configuration file.
 pub const DEFAULT_CONFIG: &str = "systeroid.conf";
@@ -45,6 +48,14 @@ pub struct Config {
     pub display_deprecated: bool,
     /// Path of the Linux kernel documentation.
     pub kernel_docs: Option<PathBuf>,
+    /// Path of the journal for recording the changes.
+    pub journal_path: Option<PathBuf>,
+    /// Directory of the tuning profiles.
+    pub profile_dir: Option<PathBuf>,
+    /// Namespaces to read and write the parameters from.
+    pub target_namespace: Option<NamespaceTarget>,
+    /// Additional validation rules for the parameter values.
+    pub validation_rules: Vec<(String, Rule)>,
     /// CLI configuration.
     pub cli: CliConfig,
     /// TUI configuration.
@@ -118,6 +129,18 @@ impl Config {
                 if let Some(kernel_docs) = general_section.get("kernel_docs") {
                     self.kernel_docs = Some(PathBuf::from(kernel_docs));
                 }
+                if let Some(journal_path) = general_section.get("journal_path") {
+                    self.journal_path = Some(PathBuf::from(journal_path));
+                }
+                if let Some(profile_dir) = general_section.get("profile_dir") {
+                    self.profile_dir = Some(PathBuf::from(profile_dir));
+                }
+            }
+            if let Some(section) = ini.section(Some("validation")) {
+                for (name, rule) in section.iter() {
+                    self.validation_rules
+                        .push((name.to_string(), Rule::from_str(rule)?));
+                }
             }
             if let Some(section) = ini.section(Some("cli")) {
                 parse_ini_flag!(self, cli, section, ignore_errors);
@@ -173,6 +196,10 @@ impl Default for Config {
         Self {
             display_deprecated: false,
             kernel_docs: None,
+            journal_path: None,
+            profile_dir: None,
+            target_namespace: None,
+            validation_rules: Vec::new(),
             cli: CliConfig {
                 ignore_errors: false,
                 quiet: false,
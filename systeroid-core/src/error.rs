@@ -27,6 +27,51 @@ pub enum Error {
     /// Error that may occur while parsing an INI document.
     #[error("INI parsing error: `{0}`")]
     IniError(#[from] ini::Error),
+    /// Error that may occur while validating a parameter value.
+    #[error("validation error: `{0}`")]
+    ValidationError(String),
+    /// Error that may occur while handling snapshots.
+    #[error("snapshot error: `{0}`")]
+    SnapshotError(String),
+    /// Error that may occur while preloading values from files.
+    #[error("preload error: `{0}`")]
+    PreloadError(String),
+    /// Error that may occur when the parameters drift from the configuration.
+    #[error("drift error: `{0}`")]
+    DriftError(String),
+    /// Error that may occur while applying the values in a transaction.
+    #[error("transaction error: `{0}`")]
+    TransactionError(String),
+    /// Error that may occur while reading or undoing the journal.
+    #[error("journal error: `{0}`")]
+    JournalError(String),
+    /// Error that may occur while loading the profiles.
+    #[error("profile error: `{0}`")]
+    ProfileError(String),
+    /// Error that may occur while parsing the audit rules or when the checks fail.
+    #[error("audit error: `{0}`")]
+    AuditError(String),
+    /// Error that may occur while exporting the values to a container runtime.
+    #[error("export error: `{0}`")]
+    ExportError(String),
+    /// Error that may occur while watching the parameters.
+    #[error("watch error: `{0}`")]
+    WatchError(String),
+    /// Error that may occur while entering a namespace.
+    #[error("namespace error: `{0}`")]
+    NamespaceError(String),
+    /// Error that may occur while reading or writing the captured values.
+    #[error("offline error: `{0}`")]
+    OfflineError(String),
+    /// Error that may occur while comparing the hosts.
+    #[error("fleet error: `{0}`")]
+    FleetError(String),
+    /// Error that may occur while parsing a query.
+    #[error("query error: `{0}`")]
+    QueryError(String),
+    /// Error that may occur when the planned changes cannot be applied.
+    #[error("dry run error: `{0}`")]
+    DryRunError(String),
 }
 
 /// Type alias for the standard [`Result`] type.
@@ -0,0 +1,208 @@
+use crate::error::Result;
+use parseit::reader;
+use serde::{Serialize, Serializer};
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
+use std::result::Result as StdResult;
+
+/// Path for reading the credentials of the current process.
+const PROC_STATUS_PATH: &str = "/proc/self/status";
+
+lazy_static! {
+    /// Credentials of the current process.
+    static ref CREDENTIALS: Credentials = Credentials::from_proc_status().unwrap_or_default();
+}
+
+/// Effective credentials of a process.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Credentials {
+    /// Effective user ID.
+    uid: u32,
+    /// Effective group ID.
+    gid: u32,
+    /// Supplementary group IDs.
+    groups: Vec<u32>,
+}
+
+impl Default for Credentials {
+    fn default() -> Self {
+        Self {
+            uid: u32::MAX,
+            gid: u32::MAX,
+            groups: Vec::new(),
+        }
+    }
+}
+
+impl Credentials {
+    /// Parses the credentials from `/proc/self/status`.
+    fn from_proc_status() -> Result<Self> {
+        Ok(Self::parse(&reader::read_to_string(PROC_STATUS_PATH)?))
+    }
+
+    /// Parses the credentials from the given process status.
+    fn parse(status: &str) -> Self {
+        let mut credentials = Self::default();
+        for line in status.lines() {
+            let Some((key, values)) = line.split_once(':') else {
+                continue;
+            };
+            let mut values = values.split_whitespace().filter_map(|v| v.parse().ok());
+            match key {
+                "Uid" => credentials.uid = values.nth(1).unwrap_or(credentials.uid),
+                "Gid" => credentials.gid = values.nth(1).unwrap_or(credentials.gid),
+                "Groups" => credentials.groups = values.collect(),
+                _ => {}
+            }
+        }
+        credentials
+    }
+}
+
+/// Access permissions of a kernel parameter.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Access {
+    /// File mode of the parameter (e.g. `0644`), if it is known.
+    #[serde(serialize_with = "serialize_mode")]
+    pub mode: Option<u32>,
+    /// Whether if the parameter is readable by the current user.
+    pub readable: bool,
+    /// Whether if the parameter is writable by the current user.
+    pub writable: bool,
+}
+
+/// Serializes the file mode in octal format.
+fn serialize_mode<S: Serializer>(mode: &Option<u32>, serializer: S) -> StdResult<S::Ok, S::Error> {
+    match mode {
+        Some(mode) => serializer.serialize_str(&format!("{mode:04o}")),
+        None => serializer.serialize_none(),
+    }
+}
+
+impl Access {
+    /// Constructs a new instance from the file at the given path.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let metadata = fs::metadata(path)?;
+        Ok(Self::new(
+            metadata.mode() & 0o7777,
+            metadata.uid(),
+            metadata.gid(),
+            &CREDENTIALS,
+        ))
+    }
+
+    /// Constructs a new instance for the given owner and credentials.
+    fn new(mode: u32, uid: u32, gid: u32, credentials: &Credentials) -> Self {
+        let permissions = if credentials.uid == uid {
+            mode >> 6
+        } else if credentials.gid == gid || credentials.groups.contains(&gid) {
+            mode >> 3
+        } else {
+            mode
+        };
+        Self {
+            mode: Some(mode),
+            readable: permissions & 0o4 != 0,
+            writable: permissions & 0o2 != 0,
+        }
+    }
+
+    /// Returns `true` if the parameter cannot be written by any user.
+    ///
+    /// Parameters with an unknown mode are not considered read-only.
+    pub fn is_read_only(&self) -> bool {
+        self.mode.is_some_and(|mode| mode & 0o222 == 0)
+    }
+
+    /// Returns `true` if the parameter cannot be read by any user.
+    pub fn is_write_only(&self) -> bool {
+        self.mode.is_some_and(|mode| mode & 0o444 == 0) && !self.is_read_only()
+    }
+}
+
+/// Filter for selecting the parameters based on their access permissions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccessFilter {
+    /// Parameters that are writable by the current user.
+    Writable,
+    /// Parameters that cannot be written by any user.
+    ReadOnly,
+}
+
+impl AccessFilter {
+    /// Returns `true` if the given access permissions match the filter.
+    pub fn matches(&self, access: &Access) -> bool {
+        match self {
+            Self::Writable => access.writable,
+            Self::ReadOnly => access.is_read_only(),
+        }
+    }
+}
+
+impl Display for Access {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}{}",
+            if self.readable { "r" } else { "-" },
+            if self.writable { "w" } else { "-" }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_access() {
+        let credentials = Credentials::parse(
+            "Name:\tsysteroid\nUid:\t1000\t1000\t1000\t1000\nGid:\t100\t100\t100\t100\nGroups:\t10 20 \n",
+        );
+        assert_eq!(
+            Credentials {
+                uid: 1000,
+                gid: 100,
+                groups: vec![10, 20],
+            },
+            credentials
+        );
+
+        let access = Access::new(0o644, 0, 0, &credentials);
+        assert!(access.readable);
+        assert!(!access.writable);
+        assert!(!access.is_read_only());
+        assert_eq!("r-", access.to_string());
+
+        let access = Access::new(0o644, 1000, 0, &credentials);
+        assert!(access.writable);
+        assert_eq!("rw", access.to_string());
+
+        let access = Access::new(0o460, 0, 20, &credentials);
+        assert!(access.readable);
+        assert!(access.writable);
+
+        let access = Access::new(0o444, 1000, 0, &credentials);
+        assert!(access.is_read_only());
+        assert!(!access.writable);
+
+        let access = Access::new(0o200, 1000, 0, &credentials);
+        assert!(access.is_write_only());
+        assert_eq!("-w", access.to_string());
+        assert!(AccessFilter::Writable.matches(&access));
+        assert!(!AccessFilter::ReadOnly.matches(&access));
+
+        assert_eq!(
+            r#"{"mode":"0644","readable":true,"writable":false}"#,
+            serde_json::to_string(&Access::new(0o644, 0, 0, &credentials)).unwrap_or_default()
+        );
+        assert!(!Access::default().is_read_only());
+        assert!(!AccessFilter::ReadOnly.matches(&Access::default()));
+        assert_eq!(
+            r#"{"mode":null,"readable":false,"writable":false}"#,
+            serde_json::to_string(&Access::default()).unwrap_or_default()
+        );
+    }
+}
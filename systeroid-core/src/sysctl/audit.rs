@@ -0,0 +1,801 @@
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::sysctl::controller::Sysctl;
+use crate::sysctl::parameter::{Parameter, ParameterValue};
+use crate::sysctl::validation;
+use crate::sysctl::PROC_PATH;
+use ini::Ini;
+use owo_colors::{OwoColorize, Stream::Stdout};
+use parseit::reader;
+use serde::{Serialize, Serializer};
+use std::fmt::{self, Display, Formatter};
+use std::io::Write;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Bundled audit rules that are based on the common hardening guides.
+pub const DEFAULT_AUDIT_RULES: &str = include_str!("../../rules/hardening.conf");
+
+/// Comparison operator of a check.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operator {
+    /// `==`
+    Equal,
+    /// `!=`
+    NotEqual,
+    /// `>=`
+    GreaterOrEqual,
+    /// `<=`
+    LessOrEqual,
+    /// `>`
+    Greater,
+    /// `<`
+    Less,
+    /// `in {..}`
+    In,
+    /// `not in {..}`
+    NotIn,
+}
+
+impl Operator {
+    /// Returns the operators along with their symbols in the order that they are parsed.
+    fn symbols() -> &'static [(&'static str, Self)] {
+        &[
+            ("==", Self::Equal),
+            ("!=", Self::NotEqual),
+            (">=", Self::GreaterOrEqual),
+            ("<=", Self::LessOrEqual),
+            (">", Self::Greater),
+            ("<", Self::Less),
+            ("in", Self::In),
+            ("not in", Self::NotIn),
+        ]
+    }
+
+    /// Returns `true` if the operator expects a set of values.
+    fn is_set(&self) -> bool {
+        matches!(self, Self::In | Self::NotIn)
+    }
+}
+
+impl Display for Operator {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let symbol = Self::symbols()
+            .iter()
+            .find(|(_, operator)| operator == self)
+            .map(|(symbol, _)| *symbol)
+            .unwrap_or_default();
+        write!(f, "{symbol}")
+    }
+}
+
+/// Check of a kernel parameter value.
+///
+/// Checks are written as the parameter name, an operator and the expected value(s):
+///
+/// ```text
+/// kernel.kptr_restrict >= 1
+/// net.ipv4.conf.all.rp_filter in {1,2}
+/// kernel.unprivileged_bpf_disabled == 1
+/// ```
+///
+/// The name can also be a section or contain `*` for matching a single component,
+/// in which case all of the matched parameters should pass the check.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Check {
+    /// Name of the kernel parameter.
+    pub name: String,
+    /// Comparison operator.
+    pub operator: Operator,
+    /// Expected values.
+    pub values: Vec<ParameterValue>,
+}
+
+impl FromStr for Check {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
+        let (name, expression) = s
+            .trim()
+            .split_once(char::is_whitespace)
+            .ok_or_else(|| Error::AuditError(format!("invalid check: {s:?}")))?;
+        let (operator, value) = Operator::symbols()
+            .iter()
+            .find_map(|(symbol, operator)| {
+                expression
+                    .trim_start()
+                    .strip_prefix(symbol)
+                    .map(|value| (*operator, value.trim()))
+            })
+            .ok_or_else(|| Error::AuditError(format!("unknown operator: {s:?}")))?;
+        let values = if operator.is_set() {
+            value
+                .strip_prefix('{')
+                .and_then(|v| v.strip_suffix('}'))
+                .ok_or_else(|| Error::AuditError(format!("expected a set of values: {s:?}")))?
+                .split(',')
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(ParameterValue::from)
+                .collect()
+        } else {
+            vec![ParameterValue::from(value)]
+        };
+        if values.is_empty() || value.is_empty() {
+            return Err(Error::AuditError(format!("missing value: {s:?}")));
+        }
+        if !operator.is_set()
+            && operator != Operator::Equal
+            && operator != Operator::NotEqual
+            && values[0].as_integers().is_none()
+        {
+            return Err(Error::AuditError(format!(
+                "expected an integer value: {s:?}"
+            )));
+        }
+        Ok(Self {
+            name: name.replace('/', "."),
+            operator,
+            values,
+        })
+    }
+}
+
+impl Display for Check {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let values = self
+            .values
+            .iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<String>>();
+        if self.operator.is_set() {
+            write!(
+                f,
+                "{} {} {{{}}}",
+                self.name,
+                self.operator,
+                values.join(",")
+            )
+        } else {
+            write!(f, "{} {} {}", self.name, self.operator, values.join(" "))
+        }
+    }
+}
+
+impl Serialize for Check {
+    fn serialize<S: Serializer>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl Check {
+    /// Returns `true` if the given value passes the check.
+    ///
+    /// Each of the integers is compared if the value contains more than one of them.
+    pub fn matches(&self, value: &ParameterValue) -> bool {
+        let compare =
+            |f: fn(&i128, &i128) -> bool| match (value.as_integers(), self.values[0].as_integers())
+            {
+                (Some(values), Some(expected)) if expected.len() == 1 => {
+                    values.iter().all(|value| f(value, &expected[0]))
+                }
+                (Some(values), Some(expected)) if values.len() == expected.len() => {
+                    values.iter().zip(expected.iter()).all(|(v1, v2)| f(v1, v2))
+                }
+                _ => false,
+            };
+        match self.operator {
+            Operator::Equal => value == &self.values[0],
+            Operator::NotEqual => value != &self.values[0],
+            Operator::GreaterOrEqual => compare(i128::ge),
+            Operator::LessOrEqual => compare(i128::le),
+            Operator::Greater => compare(i128::gt),
+            Operator::Less => compare(i128::lt),
+            Operator::In => self.values.contains(value),
+            Operator::NotIn => !self.values.contains(value),
+        }
+    }
+}
+
+/// Severity of an audit rule.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Low severity.
+    Low,
+    /// Medium severity.
+    Medium,
+    /// High severity.
+    High,
+}
+
+impl FromStr for Severity {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "low" => Ok(Self::Low),
+            "medium" => Ok(Self::Medium),
+            "high" => Ok(Self::High),
+            _ => Err(Error::AuditError(format!("unknown severity: {s:?}"))),
+        }
+    }
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Low => write!(f, "low"),
+            Self::Medium => write!(f, "medium"),
+            Self::High => write!(f, "high"),
+        }
+    }
+}
+
+impl Severity {
+    /// Returns the corresponding SARIF level.
+    fn sarif_level(&self) -> &'static str {
+        match self {
+            Self::Low => "note",
+            Self::Medium => "warning",
+            Self::High => "error",
+        }
+    }
+}
+
+/// Rule for auditing a kernel parameter.
+///
+/// Rules are read from INI files where each section is a rule:
+///
+/// ```ini
+/// [restrict-kernel-pointers]
+/// check = "kernel.kptr_restrict >= 1"
+/// severity = medium
+/// rationale = "Kernel pointers help attackers to bypass KASLR."
+/// remediation = "Set kernel.kptr_restrict to 1."
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct AuditRule {
+    /// Identifier of the rule.
+    pub id: String,
+    /// Check to perform.
+    pub check: Check,
+    /// Severity of the rule.
+    pub severity: Severity,
+    /// Reason of the check.
+    pub rationale: Option<String>,
+    /// Instructions for fixing the value.
+    pub remediation: Option<String>,
+}
+
+impl AuditRule {
+    /// Parses the rules in the given INI document.
+    pub fn parse(contents: &str) -> Result<Vec<Self>> {
+        let ini = Ini::load_from_str(contents).map_err(|e| Error::AuditError(e.to_string()))?;
+        ini.iter()
+            .filter_map(|(section, properties)| section.map(|id| (id, properties)))
+            .map(|(id, properties)| {
+                Ok(Self {
+                    id: id.to_string(),
+                    check: properties
+                        .get("check")
+                        .ok_or_else(|| Error::AuditError(format!("{id}: missing check")))?
+                        .parse()?,
+                    severity: properties
+                        .get("severity")
+                        .map(Severity::from_str)
+                        .transpose()?
+                        .unwrap_or(Severity::Medium),
+                    rationale: properties.get("rationale").map(String::from),
+                    remediation: properties.get("remediation").map(String::from),
+                })
+            })
+            .collect()
+    }
+
+    /// Loads the rules from the given file or the bundled rules.
+    pub fn load(path: Option<&Path>) -> Result<Vec<Self>> {
+        match path {
+            Some(path) => Self::parse(&reader::read_to_string(path)?)
+                .map_err(|e| Error::AuditError(format!("{}: {}", path.display(), e))),
+            None => Self::parse(DEFAULT_AUDIT_RULES),
+        }
+    }
+}
+
+/// Status of an audit result.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditStatus {
+    /// All of the values pass the check.
+    Pass,
+    /// At least one of the values does not pass the check.
+    Fail,
+    /// Parameter does not exist.
+    Skip,
+}
+
+impl Display for AuditStatus {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Pass => write!(f, "pass"),
+            Self::Fail => write!(f, "fail"),
+            Self::Skip => write!(f, "skip"),
+        }
+    }
+}
+
+/// Value of a kernel parameter that is checked.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct AuditValue {
+    /// Name of the kernel parameter.
+    pub name: String,
+    /// Current value.
+    pub value: String,
+    /// Whether if the value passes the check.
+    pub passed: bool,
+}
+
+/// Result of an audit rule.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct AuditResult {
+    /// Audit rule.
+    #[serde(flatten)]
+    pub rule: AuditRule,
+    /// Status of the result.
+    pub status: AuditStatus,
+    /// Checked values.
+    pub values: Vec<AuditValue>,
+}
+
+impl AuditResult {
+    /// Evaluates the given rule against the current values.
+    pub fn new(sysctl: &Sysctl, rule: AuditRule) -> Self {
+        let parameters = if rule.check.name.contains('*') {
+            sysctl
+                .parameters
+                .iter()
+                .filter(|parameter| validation::matches_pattern(&rule.check.name, &parameter.name))
+                .collect::<Vec<&Parameter>>()
+        } else {
+            sysctl.find_parameters(&rule.check.name)
+        };
+        let values = parameters
+            .into_iter()
+            .map(|parameter| AuditValue {
+                name: parameter.name.to_string(),
+                value: parameter.value.to_string(),
+                passed: rule.check.matches(&parameter.value),
+            })
+            .collect::<Vec<AuditValue>>();
+        let status = if values.is_empty() {
+            AuditStatus::Skip
+        } else if values.iter().all(|value| value.passed) {
+            AuditStatus::Pass
+        } else {
+            AuditStatus::Fail
+        };
+        Self {
+            rule,
+            status,
+            values,
+        }
+    }
+
+    /// Returns the values that do not pass the check.
+    pub fn get_failures(&self) -> impl Iterator<Item = &AuditValue> {
+        self.values.iter().filter(|value| !value.passed)
+    }
+}
+
+/// Escapes the special characters for XML.
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Report of an audit.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct AuditReport {
+    /// Results of the rules.
+    pub results: Vec<AuditResult>,
+}
+
+impl AuditReport {
+    /// Evaluates the given rules against the current values.
+    pub fn new(sysctl: &Sysctl, rules: Vec<AuditRule>) -> Self {
+        Self {
+            results: rules
+                .into_iter()
+                .map(|rule| AuditResult::new(sysctl, rule))
+                .collect(),
+        }
+    }
+
+    /// Returns the number of results with the given status.
+    pub fn count(&self, status: AuditStatus) -> usize {
+        self.results
+            .iter()
+            .filter(|result| result.status == status)
+            .count()
+    }
+
+    /// Prints the report as a table to the given output.
+    ///
+    /// Details of the failed checks are printed after the table.
+    pub fn display<Output: Write>(&self, config: &Config, output: &mut Output) -> Result<()> {
+        let id_width = self
+            .results
+            .iter()
+            .map(|result| result.rule.id.len())
+            .chain([4])
+            .max()
+            .unwrap_or_default();
+        let check_width = self
+            .results
+            .iter()
+            .map(|result| result.rule.check.to_string().len())
+            .chain([5])
+            .max()
+            .unwrap_or_default();
+        writeln!(
+            output,
+            "{}",
+            format!(
+                "{:<6} {:<8} {:<id_width$} {:<check_width$} VALUE",
+                "STATUS", "SEVERITY", "RULE", "CHECK"
+            )
+            .if_supports_color(Stdout, |v| v.color(config.cli.color.default_color))
+        )?;
+        for result in &self.results {
+            let status = format!("{:<6}", result.status.to_string());
+            let severity = format!("{:<8}", result.rule.severity.to_string());
+            let mut values = Vec::<String>::new();
+            for value in &result.values {
+                let value = value.value.replace('\n', " ");
+                if !values.contains(&value) {
+                    values.push(value);
+                }
+            }
+            writeln!(
+                output,
+                "{} {} {:<id_width$} {:<check_width$} {}",
+                match result.status {
+                    AuditStatus::Pass =>
+                        status.if_supports_color(Stdout, |v| v.green()).to_string(),
+                    AuditStatus::Fail => status.if_supports_color(Stdout, |v| v.red()).to_string(),
+                    AuditStatus::Skip => status
+                        .if_supports_color(Stdout, |v| v.color(config.cli.color.default_color))
+                        .to_string(),
+                },
+                match result.rule.severity {
+                    Severity::High => severity.if_supports_color(Stdout, |v| v.red()).to_string(),
+                    Severity::Medium => severity
+                        .if_supports_color(Stdout, |v| v.yellow())
+                        .to_string(),
+                    Severity::Low => severity,
+                },
+                result.rule.id,
+                result.rule.check.to_string(),
+                if values.is_empty() {
+                    String::from("-")
+                } else {
+                    values.join(", ")
+                }
+            )?;
+        }
+        for result in self
+            .results
+            .iter()
+            .filter(|result| result.status == AuditStatus::Fail)
+        {
+            writeln!(
+                output,
+                "\n{}:{}",
+                result.rule.id.if_supports_color(Stdout, |v| v.bold()),
+                result
+                    .rule
+                    .rationale
+                    .as_ref()
+                    .map(|rationale| format!(" {rationale}"))
+                    .unwrap_or_default()
+            )?;
+            for value in result.get_failures() {
+                writeln!(
+                    output,
+                    "  {} {} {}",
+                    value.name,
+                    "=".if_supports_color(Stdout, |v| v.color(config.cli.color.default_color)),
+                    value.value.replace('\n', " ")
+                )?;
+            }
+            if let Some(remediation) = &result.rule.remediation {
+                writeln!(
+                    output,
+                    "  {} {}",
+                    "remediation:"
+                        .if_supports_color(Stdout, |v| v.color(config.cli.color.default_color)),
+                    remediation
+                )?;
+            }
+        }
+        writeln!(
+            output,
+            "\n{} checks: {} passed, {} failed, {} skipped",
+            self.results.len(),
+            self.count(AuditStatus::Pass),
+            self.count(AuditStatus::Fail),
+            self.count(AuditStatus::Skip)
+        )?;
+        Ok(())
+    }
+
+    /// Prints the results in JSON format.
+    pub fn display_json<Output: Write>(&self, output: &mut Output) -> Result<()> {
+        writeln!(output, "{}", serde_json::to_string(&self.results)?)?;
+        Ok(())
+    }
+
+    /// Prints the report in JUnit XML format.
+    ///
+    /// Each rule is a test case that is skipped if the parameter does not exist.
+    pub fn display_junit<Output: Write>(&self, output: &mut Output) -> Result<()> {
+        let counts = format!(
+            "tests=\"{}\" failures=\"{}\" skipped=\"{}\"",
+            self.results.len(),
+            self.count(AuditStatus::Fail),
+            self.count(AuditStatus::Skip)
+        );
+        writeln!(output, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+        writeln!(
+            output,
+            "<testsuites name=\"{}\" {counts}>",
+            env!("CARGO_PKG_NAME").split('-').collect::<Vec<_>>()[0],
+        )?;
+        writeln!(output, "  <testsuite name=\"audit\" {counts}>")?;
+        for result in &self.results {
+            write!(
+                output,
+                "    <testcase name=\"{}\" classname=\"{}\"",
+                escape_xml(&result.rule.id),
+                escape_xml(&result.rule.check.name)
+            )?;
+            match result.status {
+                AuditStatus::Pass => writeln!(output, "/>")?,
+                AuditStatus::Fail => {
+                    let mut details = result
+                        .get_failures()
+                        .map(|value| format!("{} = {}", value.name, value.value))
+                        .collect::<Vec<String>>();
+                    details.extend(result.rule.rationale.clone());
+                    details.extend(result.rule.remediation.clone());
+                    writeln!(output, ">")?;
+                    writeln!(
+                        output,
+                        "      <failure type=\"{}\" message=\"{}\">{}</failure>",
+                        result.rule.severity,
+                        escape_xml(&result.rule.check.to_string()),
+                        escape_xml(&details.join("\n"))
+                    )?;
+                    writeln!(output, "    </testcase>")?;
+                }
+                AuditStatus::Skip => {
+                    writeln!(output, ">")?;
+                    writeln!(
+                        output,
+                        "      <skipped message=\"{}: no such parameter\"/>",
+                        escape_xml(&result.rule.check.name)
+                    )?;
+                    writeln!(output, "    </testcase>")?;
+                }
+            }
+        }
+        writeln!(output, "  </testsuite>")?;
+        writeln!(output, "</testsuites>")?;
+        Ok(())
+    }
+
+    /// Prints the report in SARIF format.
+    ///
+    /// Each of the values that do not pass a check is reported as a result.
+    pub fn display_sarif<Output: Write>(&self, output: &mut Output) -> Result<()> {
+        let rules = self
+            .results
+            .iter()
+            .map(|result| {
+                serde_json::json!({
+                    "id": result.rule.id,
+                    "shortDescription": { "text": result.rule.check.to_string() },
+                    "fullDescription": {
+                        "text": result.rule.rationale.clone()
+                            .unwrap_or_else(|| result.rule.check.to_string())
+                    },
+                    "help": { "text": result.rule.remediation.clone().unwrap_or_default() },
+                    "defaultConfiguration": { "level": result.rule.severity.sarif_level() },
+                    "properties": { "severity": result.rule.severity }
+                })
+            })
+            .collect::<Vec<_>>();
+        let results = self
+            .results
+            .iter()
+            .enumerate()
+            .flat_map(|(index, result)| {
+                result.get_failures().map(move |value| {
+                    serde_json::json!({
+                        "ruleId": result.rule.id,
+                        "ruleIndex": index,
+                        "level": result.rule.severity.sarif_level(),
+                        "message": {
+                            "text": format!(
+                                "{} = {} (expected: {})",
+                                value.name, value.value, result.rule.check
+                            )
+                        },
+                        "locations": [{
+                            "physicalLocation": {
+                                "artifactLocation": {
+                                    "uri": format!(
+                                        "file://{}{}",
+                                        PROC_PATH,
+                                        value.name.replace('.', "/")
+                                    )
+                                }
+                            }
+                        }]
+                    })
+                })
+            })
+            .collect::<Vec<_>>();
+        let sarif = serde_json::json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": env!("CARGO_PKG_NAME").split('-').collect::<Vec<_>>()[0],
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "informationUri": env!("CARGO_PKG_HOMEPAGE"),
+                        "rules": rules
+                    }
+                },
+                "results": results
+            }]
+        });
+        writeln!(output, "{}", serde_json::to_string_pretty(&sarif)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sysctl::backend::DirectoryBackend;
+    use std::fs;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_audit() -> Result<()> {
+        assert!(AuditRule::parse(DEFAULT_AUDIT_RULES)?.len() > 20);
+        assert_eq!(
+            Check {
+                name: String::from("net.ipv4.conf.all.rp_filter"),
+                operator: Operator::In,
+                values: vec![ParameterValue::from("1"), ParameterValue::from("2")],
+            },
+            Check::from_str("net.ipv4.conf.all.rp_filter in { 1, 2 }")?
+        );
+        assert_eq!(
+            "vm.swappiness not in {0,1}",
+            Check::from_str("vm/swappiness not in {0,1}")?.to_string()
+        );
+        for check in [
+            "kernel.kptr_restrict",
+            "kernel.kptr_restrict => 1",
+            "kernel.kptr_restrict in 1",
+            "kernel.kptr_restrict >= ",
+            "kernel.hostname > abc",
+        ] {
+            assert!(Check::from_str(check).is_err());
+        }
+        let check = Check::from_str("net.ipv4.tcp_rmem >= 4096")?;
+        assert!(check.matches(&ParameterValue::from("4096 131072 6291456")));
+        assert!(!check.matches(&ParameterValue::from("1024 131072 6291456")));
+
+        let temp_dir = tempfile::tempdir()?;
+        let root = temp_dir.path().to_path_buf();
+        fs::create_dir_all(root.join("kernel"))?;
+        fs::create_dir_all(root.join("net").join("ipv4").join("conf").join("all"))?;
+        fs::create_dir_all(root.join("net").join("ipv4").join("conf").join("eth0"))?;
+        fs::write(root.join("kernel").join("kptr_restrict"), "0\n")?;
+        fs::write(root.join("kernel").join("dmesg_restrict"), "1\n")?;
+        for (interface, value) in [("all", "1\n"), ("eth0", "0\n")] {
+            fs::write(
+                root.join("net")
+                    .join("ipv4")
+                    .join("conf")
+                    .join(interface)
+                    .join("rp_filter"),
+                value,
+            )?;
+        }
+        let sysctl =
+            Sysctl::init_with_backend(Config::default(), Arc::new(DirectoryBackend::new(&root)))?;
+        let report = AuditReport::new(
+            &sysctl,
+            AuditRule::parse(
+                r#"
+                [kptr]
+                check = "kernel.kptr_restrict >= 1"
+                severity = high
+                rationale = "Hide kernel pointers."
+                remediation = "Set kernel.kptr_restrict to 1."
+
+                [dmesg]
+                check = "kernel.dmesg_restrict == 1"
+                severity = low
+
+                [rp-filter]
+                check = "net.ipv4.conf.*.rp_filter in {1,2}"
+
+                [bpf]
+                check = "kernel.unprivileged_bpf_disabled == 1"
+                "#,
+            )?,
+        );
+        assert_eq!(
+            vec![
+                AuditStatus::Fail,
+                AuditStatus::Pass,
+                AuditStatus::Fail,
+                AuditStatus::Skip
+            ],
+            report
+                .results
+                .iter()
+                .map(|result| result.status)
+                .collect::<Vec<AuditStatus>>()
+        );
+        assert_eq!(
+            vec!["net.ipv4.conf.eth0.rp_filter"],
+            report.results[2]
+                .get_failures()
+                .map(|value| value.name.as_str())
+                .collect::<Vec<&str>>()
+        );
+
+        let mut output = Vec::new();
+        report.display(&sysctl.config, &mut output)?;
+        let output = String::from_utf8_lossy(&output);
+        assert!(output.contains("fail   high     kptr      kernel.kptr_restrict >= 1"));
+        assert!(output.contains("\nkptr: Hide kernel pointers.\n  kernel.kptr_restrict = 0\n"));
+        assert!(output.ends_with("4 checks: 1 passed, 2 failed, 1 skipped\n"));
+
+        let mut output = Vec::new();
+        report.display_junit(&mut output)?;
+        let output = String::from_utf8_lossy(&output);
+        assert!(
+            output.contains("<testsuite name=\"audit\" tests=\"4\" failures=\"2\" skipped=\"1\">")
+        );
+        assert!(output.contains("<failure type=\"high\" message=\"kernel.kptr_restrict &gt;= 1\">"));
+
+        let mut output = Vec::new();
+        report.display_sarif(&mut output)?;
+        let sarif = serde_json::from_slice::<serde_json::Value>(&output)?;
+        assert_eq!(
+            2,
+            sarif["runs"][0]["results"]
+                .as_array()
+                .map(Vec::len)
+                .unwrap_or_default()
+        );
+        assert_eq!(
+            "file:///proc/sys/net/ipv4/conf/eth0/rp_filter",
+            sarif["runs"][0]["results"][1]["locations"][0]["physicalLocation"]["artifactLocation"]
+                ["uri"]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,357 @@
+use crate::error::{Error, Result};
+use crate::sysctl::access::Access;
+use crate::sysctl::parameter::{Parameter, ParameterValue};
+use crate::sysctl::section::Section;
+use crate::sysctl::source::{self, ParameterValues};
+use crate::sysctl::PROC_PATH;
+use parseit::reader;
+use std::fmt::Debug;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::result::Result as StdResult;
+use sysctl::{Ctl, CtlFlags, CtlIter, Sysctl as SysctlImpl, SysctlError};
+
+/// Source for reading and writing the kernel parameters.
+pub trait Backend: Debug + Send + Sync {
+    /// Returns the available kernel parameters.
+    fn get_parameters(&self) -> Result<Vec<Parameter>>;
+
+    /// Reads the current value of the given parameter.
+    fn read_value(&self, name: &str) -> Result<String>;
+
+    /// Writes the given value to the parameter and returns the value after the write.
+    fn write_value(&self, name: &str, value: &str) -> Result<String>;
+
+    /// Returns the path of the given parameter.
+    fn get_path(&self, name: &str) -> PathBuf {
+        Path::new(PROC_PATH).join(name.replace('.', "/"))
+    }
+}
+
+/// Backend for the running kernel.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct KernelBackend;
+
+impl Backend for KernelBackend {
+    fn get_parameters(&self) -> Result<Vec<Parameter>> {
+        CtlIter::root()
+            .filter_map(StdResult::ok)
+            .filter(|ctl| {
+                ctl.flags()
+                    .map(|flags| !flags.contains(CtlFlags::SKIP))
+                    .unwrap_or(false)
+            })
+            .filter_map(|ctl| match Parameter::try_from(&ctl) {
+                Ok(parameter) => Some(Ok(parameter)),
+                Err(e) => match ctl.name() {
+                    Ok(name) => {
+                        log::trace!(target: "sysctl", "{} ({})", e, name);
+                        None
+                    }
+                    Err(e) => Some(Err(Error::from(e))),
+                },
+            })
+            .collect()
+    }
+
+    fn read_value(&self, name: &str) -> Result<String> {
+        Ok(Ctl::new(name)?.value_string()?)
+    }
+
+    fn write_value(&self, name: &str, value: &str) -> Result<String> {
+        Ok(Ctl::new(name)?.set_value_string(value)?)
+    }
+}
+
+/// Backend for a directory tree that is shaped like `/proc/sys`.
+///
+/// This is useful for inspecting chroots, mounted images and test fixtures.
+#[derive(Clone, Debug)]
+pub struct DirectoryBackend {
+    /// Root directory of the tree.
+    root: PathBuf,
+}
+
+impl DirectoryBackend {
+    /// Constructs a new instance for the given root directory.
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Returns the parameter name of the given file in the tree.
+    fn get_name(&self, path: &Path) -> Option<String> {
+        let components = path
+            .strip_prefix(&self.root)
+            .ok()?
+            .components()
+            .map(|component| component.as_os_str().to_str())
+            .collect::<Option<Vec<&str>>>()?;
+        Some(components.join("."))
+    }
+
+    /// Collects the files in the given directory recursively.
+    fn walk(path: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+        let mut entries = fs::read_dir(path)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<StdResult<Vec<PathBuf>, _>>()?;
+        entries.sort();
+        for entry in entries {
+            let file_type = fs::symlink_metadata(&entry)?.file_type();
+            if file_type.is_dir() {
+                Self::walk(&entry, files)?;
+            } else if file_type.is_file() {
+                files.push(entry);
+            }
+        }
+        Ok(())
+    }
+
+    /// Maps the "not found" errors to the corresponding sysctl error.
+    fn map_error(name: &str, error: std::io::Error) -> Error {
+        if error.kind() == ErrorKind::NotFound {
+            Error::from(SysctlError::NotFound(name.to_string()))
+        } else {
+            Error::from(error)
+        }
+    }
+}
+
+impl Backend for DirectoryBackend {
+    fn get_parameters(&self) -> Result<Vec<Parameter>> {
+        let mut files = Vec::new();
+        Self::walk(&self.root, &mut files)?;
+        Ok(files
+            .iter()
+            .filter_map(|path| {
+                let name = self.get_name(path)?;
+                match self.read_value(&name) {
+                    Ok(value) => Some(Parameter {
+                        section: Section::from_name(name.clone()),
+                        name,
+                        value: ParameterValue::from(value),
+                        description: None,
+                        docs_path: PathBuf::new(),
+                        docs_title: String::new(),
+                        access: Access::from_path(path).unwrap_or_default(),
+                    }),
+                    Err(e) => {
+                        log::trace!(target: "sysctl", "{} ({})", e, name);
+                        None
+                    }
+                }
+            })
+            .collect())
+    }
+
+    fn read_value(&self, name: &str) -> Result<String> {
+        fs::read_to_string(self.get_path(name))
+            .map(|value| value.trim().to_string())
+            .map_err(|e| Self::map_error(name, e))
+    }
+
+    fn write_value(&self, name: &str, value: &str) -> Result<String> {
+        let mut file = OpenOptions::new()
+            .write(true)
+            .truncate(true)
+            .open(self.get_path(name))
+            .map_err(|e| Self::map_error(name, e))?;
+        writeln!(file, "{value}")?;
+        self.read_value(name)
+    }
+
+    fn get_path(&self, name: &str) -> PathBuf {
+        self.root.join(name.replace('.', "/"))
+    }
+}
+
+/// Backend for the values that are captured from another system.
+///
+/// The values are read from a `sysctl -a` dump, JSON output, snapshot or the `proc/sys`
+/// tree of a sosreport and they cannot be written.
+#[derive(Clone, Debug, Default)]
+pub struct OfflineBackend {
+    /// Captured values of the parameters.
+    values: ParameterValues,
+    /// File or directory that the values are loaded from.
+    path: PathBuf,
+}
+
+impl OfflineBackend {
+    /// Constructs a new instance for the given values.
+    pub fn new(values: ParameterValues) -> Self {
+        Self {
+            values,
+            path: PathBuf::new(),
+        }
+    }
+
+    /// Loads the values from the given file or directory.
+    ///
+    /// A directory is read as a sosreport (i.e. from its `proc/sys` tree if it exists)
+    /// and the format of a file is detected from its contents.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if path.is_dir() {
+            let root = path.join("proc").join("sys");
+            let root = if root.is_dir() {
+                root
+            } else {
+                path.to_path_buf()
+            };
+            Ok(Self {
+                values: DirectoryBackend::new(&root)
+                    .get_parameters()?
+                    .into_iter()
+                    .map(|parameter| (parameter.name, parameter.value.to_string()))
+                    .collect(),
+                path: root,
+            })
+        } else {
+            Ok(Self {
+                values: source::parse_values(&reader::read_to_string(path)?)?,
+                path: path.to_path_buf(),
+            })
+        }
+    }
+}
+
+impl Backend for OfflineBackend {
+    fn get_parameters(&self) -> Result<Vec<Parameter>> {
+        Ok(self
+            .values
+            .iter()
+            .map(|(name, value)| Parameter {
+                section: Section::from_name(name.to_string()),
+                name: name.to_string(),
+                value: ParameterValue::from(value.as_str()),
+                description: None,
+                docs_path: PathBuf::new(),
+                docs_title: String::new(),
+                access: Access {
+                    mode: Some(0o444),
+                    readable: true,
+                    writable: false,
+                },
+            })
+            .collect())
+    }
+
+    fn read_value(&self, name: &str) -> Result<String> {
+        self.values
+            .get(&name.replace('/', "."))
+            .cloned()
+            .ok_or_else(|| Error::from(SysctlError::NotFound(name.to_string())))
+    }
+
+    fn write_value(&self, name: &str, _: &str) -> Result<String> {
+        Err(Error::OfflineError(format!(
+            "cannot set {name}: values cannot be written in offline mode"
+        )))
+    }
+
+    fn get_path(&self, name: &str) -> PathBuf {
+        if self.path.is_file() {
+            PathBuf::from(format!("{}:{}", self.path.display(), name))
+        } else {
+            self.path.join(name.replace('.', "/"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_directory_backend() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let root = temp_dir.path().to_path_buf();
+        fs::create_dir_all(root.join("kernel"))?;
+        fs::create_dir_all(root.join("net").join("ipv4"))?;
+        fs::write(root.join("kernel").join("hostname"), "localhost\n")?;
+        fs::write(
+            root.join("net").join("ipv4").join("tcp_rmem"),
+            "4096\t131072\t6291456\n",
+        )?;
+
+        let backend = DirectoryBackend::new(&root);
+        let parameters = backend.get_parameters()?;
+        assert_eq!(
+            vec!["kernel.hostname", "net.ipv4.tcp_rmem"],
+            parameters
+                .iter()
+                .map(|p| p.name.as_str())
+                .collect::<Vec<&str>>()
+        );
+        assert_eq!(Section::Net, parameters[1].section);
+        assert_eq!(
+            ParameterValue::from("4096 131072 6291456"),
+            parameters[1].value
+        );
+
+        assert_eq!(
+            "example",
+            backend.write_value("kernel.hostname", "example")?
+        );
+        assert_eq!("example", backend.read_value("kernel/hostname")?);
+        assert!(backend.read_value("kernel.unexisting").is_err());
+        assert!(backend.write_value("kernel.unexisting", "1").is_err());
+        assert_eq!(
+            root.join("vm/swappiness"),
+            backend.get_path("vm.swappiness")
+        );
+        assert_eq!(
+            Path::new("/proc/sys/vm/swappiness"),
+            KernelBackend.get_path("vm.swappiness")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_offline_backend() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let root = temp_dir.path().to_path_buf();
+        fs::create_dir_all(root.join("sosreport").join("proc").join("sys").join("vm"))?;
+        fs::write(
+            root.join("sosreport")
+                .join("proc")
+                .join("sys")
+                .join("vm")
+                .join("swappiness"),
+            "10\n",
+        )?;
+        fs::write(
+            root.join("sysctl.txt"),
+            "kernel.hostname = example\nnet.ipv4.tcp_rmem = 4096\t131072\t6291456\n",
+        )?;
+
+        let backend = OfflineBackend::load(root.join("sysctl.txt"))?;
+        let parameters = backend.get_parameters()?;
+        assert_eq!(
+            vec!["kernel.hostname", "net.ipv4.tcp_rmem"],
+            parameters
+                .iter()
+                .map(|p| p.name.as_str())
+                .collect::<Vec<&str>>()
+        );
+        assert!(parameters[1].access.is_read_only());
+        assert_eq!(
+            ParameterValue::from("4096 131072 6291456"),
+            parameters[1].value
+        );
+        assert_eq!("example", backend.read_value("kernel/hostname")?);
+        assert!(backend.write_value("kernel.hostname", "localhost").is_err());
+
+        let backend = OfflineBackend::load(root.join("sosreport"))?;
+        assert_eq!("10", backend.read_value("vm.swappiness")?);
+        assert_eq!(
+            root.join("sosreport/proc/sys/vm/swappiness"),
+            backend.get_path("vm.swappiness")
+        );
+        assert!(backend.read_value("kernel.hostname").is_err());
+        Ok(())
+    }
+}
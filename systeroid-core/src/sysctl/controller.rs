@@ -1,23 +1,23 @@
 use crate::cache::{Cache, CacheData};
 use crate::config::Config;
-use crate::error::{Error, Result};
+use crate::error::Result;
 use crate::parsers::{parse_kernel_docs, KERNEL_DOCS_PATH};
-use crate::sysctl::parameter::Parameter;
+use crate::sysctl::backend::{Backend, KernelBackend};
+use crate::sysctl::document::Document;
+use crate::sysctl::parameter::{Parameter, ParameterValue};
 use crate::sysctl::section::Section;
+use crate::sysctl::transaction::Change;
 use crate::sysctl::{
-    DEFAULT_PRELOAD, DEPRECATED_PARAMS, DISABLE_CACHE_ENV, PARAMETERS_CACHE_LABEL, PROC_PATH,
+    DEFAULT_PRELOAD, DEPRECATED_PARAMS, DISABLE_CACHE_ENV, PARAMETERS_CACHE_LABEL, SAVE_FILE_HEADER,
 };
 use parseit::globwalk;
-use parseit::reader;
 use rayon::prelude::*;
-use std::convert::TryFrom;
 use std::env;
-use std::fs::{File, OpenOptions};
-use std::io::Write;
+use std::fs;
 use std::ops::Not;
 use std::path::{Path, PathBuf};
 use std::result::Result as StdResult;
-use sysctl::{CtlFlags, CtlIter, Sysctl as SysctlImpl};
+use std::sync::Arc;
 
 /// Sysctl wrapper for managing the kernel parameters.
 #[derive(Clone, Debug)]
@@ -26,42 +26,75 @@ pub struct Sysctl {
     pub parameters: Vec<Parameter>,
     /// Configuration.
     pub config: Config,
+    /// Backend for reading/writing the kernel parameters.
+    pub backend: Arc<dyn Backend>,
 }
 
 impl Sysctl {
     /// Constructs a new instance by fetching the available kernel parameters.
+    ///
+    /// The target namespaces in the configuration are entered first so that the
+    /// namespaced parameters are read and written as seen from them.
     pub fn init(config: Config) -> Result<Self> {
-        let parameters = CtlIter::root()
-            .filter_map(StdResult::ok)
-            .filter(|ctl| {
-                ctl.flags()
-                    .map(|flags| !flags.contains(CtlFlags::SKIP))
-                    .unwrap_or(false)
+        if let Some(target) = &config.target_namespace {
+            target.enter()?;
+        }
+        Self::init_with_backend(config, Arc::new(KernelBackend))
+    }
+
+    /// Constructs a new instance by fetching the kernel parameters from the given backend.
+    pub fn init_with_backend(config: Config, backend: Arc<dyn Backend>) -> Result<Self> {
+        let parameters = backend
+            .get_parameters()?
+            .into_iter()
+            .filter(|parameter| {
+                config.display_deprecated
+                    || parameter
+                        .get_absolute_name()
+                        .map(|pname| DEPRECATED_PARAMS.contains(&pname))
+                        .unwrap_or(false)
+                        .not()
             })
-            .filter_map(|ctl| match Parameter::try_from(&ctl) {
-                Ok(parameter) => {
-                    if !config.display_deprecated {
-                        parameter
-                            .get_absolute_name()
-                            .map(|pname| DEPRECATED_PARAMS.contains(&pname))
-                            .unwrap_or(false)
-                            .not()
-                            .then_some(Ok(parameter))
-                    } else {
-                        Some(Ok(parameter))
+            .collect();
+        Ok(Self {
+            parameters,
+            config,
+            backend,
+        })
+    }
+
+    /// Re-reads the values of the parameters that match the given filter.
+    ///
+    /// Unlike [`Sysctl::init`], the parameters are not re-scanned.
+    /// Returns the changes of the values since the last read.
+    pub fn refresh<F>(&mut self, filter: F) -> Vec<Change>
+    where
+        F: Fn(&Parameter) -> bool + Sync,
+    {
+        let backend = self.backend.clone();
+        self.parameters
+            .par_iter_mut()
+            .filter(|parameter| filter(parameter))
+            .filter_map(|parameter| {
+                let value = match backend.read_value(&parameter.name) {
+                    Ok(value) => ParameterValue::from(value),
+                    Err(e) => {
+                        log::trace!(target: "sysctl", "Cannot read {:?}: {}", parameter.name, e);
+                        return None;
                     }
+                };
+                if value == parameter.value {
+                    return None;
                 }
-                Err(e) => match ctl.name() {
-                    Ok(name) => {
-                        log::trace!(target: "sysctl", "{} ({})", e, name);
-                        None
-                    }
-                    Err(e) => Some(Err(Error::from(e))),
-                },
+                let change = Change {
+                    name: parameter.name.to_string(),
+                    old_value: parameter.value.to_string(),
+                    new_value: value.to_string(),
+                };
+                parameter.value = value;
+                Some(change)
             })
-            .collect::<Result<Vec<_>>>()?;
-
-        Ok(Self { parameters, config })
+            .collect()
     }
 
     /// Returns the first found parameter in the available parameters.
@@ -73,28 +106,32 @@ impl Sysctl {
     /// Returns the parameters that matches the given query.
     pub fn get_parameters(&self, query: &str) -> Vec<&Parameter> {
         log::trace!(target: "sysctl", "Querying parameters: {:?}", query);
-        let query = query.replace('/', ".");
-        let parameters = self
-            .parameters
-            .iter()
-            .filter(|param| {
-                param.name == query
-                    || param.get_absolute_name() == Some(&query)
-                    || param.is_in_section(&query)
-            })
-            .collect::<Vec<&Parameter>>();
+        let parameters = self.find_parameters(query);
         if parameters.is_empty() && !self.config.cli.ignore_errors {
             log::error!(
                 target: "sysctl",
-                "{}: cannot stat {}{}: No such file or directory",
+                "{}: cannot stat {}: No such file or directory",
                 env!("CARGO_PKG_NAME").split('-').collect::<Vec<_>>()[0],
-                PROC_PATH,
-                query.replace('.', "/")
+                self.backend.get_path(query).display()
             )
         }
         parameters
     }
 
+    /// Returns the parameters that match the given query without logging an error
+    /// if there is no match.
+    pub fn find_parameters(&self, query: &str) -> Vec<&Parameter> {
+        let query = query.replace('/', ".");
+        self.parameters
+            .iter()
+            .filter(|param| {
+                param.name == query
+                    || param.get_absolute_name() == Some(&query)
+                    || param.is_in_section(&query)
+            })
+            .collect()
+    }
+
     /// Updates the descriptions of the kernel parameters using the given cached data.
     pub fn update_docs_from_cache(&mut self, cache: &Cache) -> Result<()> {
         log::trace!(target: "cache", "{:?}", cache);
@@ -190,6 +227,8 @@ impl Sysctl {
     }
 
     /// Saves the parameter values to the given file.
+    ///
+    /// The file is created with a header if it does not exist.
     pub fn save_to_file(
         &self,
         param_name: String,
@@ -206,25 +245,16 @@ impl Sysctl {
             param_name,
             save_path
         );
-        let data = format!("{param_name} = {new_value}");
-        if save_path.exists() {
-            let contents = reader::read_to_string(&save_path)?;
-            let mut lines = contents.split('\n').collect::<Vec<&str>>();
-            if let Some(line) = lines.iter_mut().find(|v| v.starts_with(&param_name)) {
-                *line = &data;
-            } else {
-                lines.push(&data);
-            }
-            let mut file = OpenOptions::new()
-                .write(true)
-                .create(false)
-                .truncate(false)
-                .open(&save_path)?;
-            file.write_all(lines.join("\n").as_bytes())?;
+        let mut document = if save_path.exists() {
+            Document::from_file(&save_path)?
         } else {
-            let mut file = File::create(&save_path)?;
-            file.write_all(data.as_bytes())?;
-        }
+            if let Some(parent) = save_path.parent().filter(|v| !v.as_os_str().is_empty()) {
+                fs::create_dir_all(parent)?;
+            }
+            Document::parse(SAVE_FILE_HEADER)
+        };
+        document.set(&param_name, &new_value);
+        document.save_to_file(&save_path)?;
         Ok(save_path)
     }
 }
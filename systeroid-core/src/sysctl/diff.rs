@@ -0,0 +1,231 @@
+use crate::config::Config;
+use crate::error::Result;
+use crate::sysctl::parameter::ParameterValue;
+use crate::sysctl::source::ParameterValues;
+use owo_colors::{OwoColorize, Stream::Stdout};
+use serde::Serialize;
+use std::fmt::{self, Display, Formatter};
+use std::io::Write;
+
+/// Kind of a difference between two parameter sources.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffKind {
+    /// Parameter only exists in the new source.
+    Added,
+    /// Parameter only exists in the old source.
+    Removed,
+    /// Parameter has different values.
+    Changed,
+}
+
+impl Display for DiffKind {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Added => "+",
+                Self::Removed => "-",
+                Self::Changed => "~",
+            }
+        )
+    }
+}
+
+/// Difference of a kernel parameter between two sources.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ParameterDiff {
+    /// Name of the kernel parameter.
+    pub name: String,
+    /// Kind of the difference.
+    pub kind: DiffKind,
+    /// Value in the old source.
+    pub old_value: Option<String>,
+    /// Value in the new source.
+    pub new_value: Option<String>,
+}
+
+impl ParameterDiff {
+    /// Compares the given parameter values and returns the differences.
+    ///
+    /// Values are compared after parsing so that the whitespace differences are ignored.
+    pub fn compute(old: &ParameterValues, new: &ParameterValues) -> Vec<Self> {
+        let mut diffs = old
+            .iter()
+            .filter_map(|(name, old_value)| match new.get(name) {
+                Some(new_value)
+                    if ParameterValue::from(old_value.as_str())
+                        == ParameterValue::from(new_value.as_str()) =>
+                {
+                    None
+                }
+                new_value => Some(Self {
+                    name: name.to_string(),
+                    kind: if new_value.is_some() {
+                        DiffKind::Changed
+                    } else {
+                        DiffKind::Removed
+                    },
+                    old_value: Some(old_value.to_string()),
+                    new_value: new_value.cloned(),
+                }),
+            })
+            .chain(new.iter().filter(|(name, _)| !old.contains_key(*name)).map(
+                |(name, new_value)| Self {
+                    name: name.to_string(),
+                    kind: DiffKind::Added,
+                    old_value: None,
+                    new_value: Some(new_value.to_string()),
+                },
+            ))
+            .collect::<Vec<Self>>();
+        diffs.sort_by(|a, b| a.name.cmp(&b.name));
+        diffs
+    }
+
+    /// Returns the formatted values of the difference.
+    fn get_values(&self) -> String {
+        let format_value = |value: &Option<String>| {
+            value
+                .as_deref()
+                .unwrap_or_default()
+                .replace('\n', " ")
+                .if_supports_color(Stdout, |v| v.bold())
+                .to_string()
+        };
+        match self.kind {
+            DiffKind::Added => format_value(&self.new_value),
+            DiffKind::Removed => format_value(&self.old_value),
+            DiffKind::Changed => format!(
+                "{} -> {}",
+                format_value(&self.old_value),
+                format_value(&self.new_value)
+            ),
+        }
+    }
+
+    /// Returns the colored symbol of the difference kind.
+    fn get_colored_kind(&self) -> String {
+        match self.kind {
+            DiffKind::Added => self
+                .kind
+                .if_supports_color(Stdout, |v| v.green())
+                .to_string(),
+            DiffKind::Removed => self.kind.if_supports_color(Stdout, |v| v.red()).to_string(),
+            DiffKind::Changed => self
+                .kind
+                .if_supports_color(Stdout, |v| v.yellow())
+                .to_string(),
+        }
+    }
+
+    /// Returns the components of the difference to construct a [`Tree`].
+    ///
+    /// [`Tree`]: crate::tree::Tree
+    pub fn get_tree_components(&self, config: &Config) -> Vec<String> {
+        let mut components = self
+            .name
+            .split('.')
+            .map(String::from)
+            .collect::<Vec<String>>();
+        if let Some(last_component) = components.last_mut() {
+            *last_component = format!(
+                "{} {} {} {}",
+                self.get_colored_kind(),
+                last_component,
+                "=".if_supports_color(Stdout, |v| v.color(config.cli.color.default_color)),
+                self.get_values()
+            );
+        }
+        components
+    }
+
+    /// Prints the difference to the given output.
+    pub fn display<Output: Write>(&self, config: &Config, output: &mut Output) -> Result<()> {
+        writeln!(
+            output,
+            "{} {} {} {}",
+            self.get_colored_kind(),
+            self.name,
+            "=".if_supports_color(Stdout, |v| v.color(config.cli.color.default_color)),
+            self.get_values()
+        )?;
+        Ok(())
+    }
+
+    /// Prints the given differences in JSON format.
+    pub fn display_bulk_json<Output: Write>(diffs: &[Self], output: &mut Output) -> Result<()> {
+        writeln!(output, "{}", serde_json::to_string(diffs)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parameter_diff() -> Result<()> {
+        let old = ParameterValues::from([
+            (String::from("kernel.hostname"), String::from("localhost")),
+            (
+                String::from("net.ipv4.tcp_rmem"),
+                String::from("4096 131072"),
+            ),
+            (String::from("vm.swappiness"), String::from("60")),
+        ]);
+        let new = ParameterValues::from([
+            (String::from("kernel.ostype"), String::from("Linux")),
+            (
+                String::from("net.ipv4.tcp_rmem"),
+                String::from("4096\t131072"),
+            ),
+            (String::from("vm.swappiness"), String::from("10")),
+        ]);
+        let diffs = ParameterDiff::compute(&old, &new);
+        assert_eq!(
+            vec![
+                ParameterDiff {
+                    name: String::from("kernel.hostname"),
+                    kind: DiffKind::Removed,
+                    old_value: Some(String::from("localhost")),
+                    new_value: None,
+                },
+                ParameterDiff {
+                    name: String::from("kernel.ostype"),
+                    kind: DiffKind::Added,
+                    old_value: None,
+                    new_value: Some(String::from("Linux")),
+                },
+                ParameterDiff {
+                    name: String::from("vm.swappiness"),
+                    kind: DiffKind::Changed,
+                    old_value: Some(String::from("60")),
+                    new_value: Some(String::from("10")),
+                },
+            ],
+            diffs
+        );
+
+        let config = Config::default();
+        let mut output = Vec::new();
+        diffs[2].display(&config, &mut output)?;
+        assert_eq!(
+            "~ vm.swappiness = 60 -> 10\n",
+            String::from_utf8_lossy(&output)
+        );
+        assert_eq!(
+            vec![String::from("kernel"), String::from("+ ostype = Linux")],
+            diffs[1].get_tree_components(&config)
+        );
+
+        let mut output = Vec::new();
+        ParameterDiff::display_bulk_json(&diffs[..1], &mut output)?;
+        assert_eq!(
+            "[{\"name\":\"kernel.hostname\",\"kind\":\"removed\",\"old_value\":\"localhost\",\"new_value\":null}]\n",
+            String::from_utf8_lossy(&output)
+        );
+        Ok(())
+    }
+}
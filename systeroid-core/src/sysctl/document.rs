@@ -0,0 +1,261 @@
+use crate::error::Result;
+use crate::sysctl::preload::Assignment;
+use parseit::reader;
+use std::fmt::{self, Display, Formatter};
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+/// Key-value entry in a sysctl.conf document.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Entry {
+    /// Text before the value (indentation, key, separator and spacing).
+    prefix: String,
+    /// Key as it is written (without the `-` prefix).
+    key: String,
+    /// Normalized name of the parameter.
+    name: String,
+    /// Value of the entry.
+    value: String,
+    /// Text after the value (trailing whitespace and the line ending).
+    suffix: String,
+    /// Whether if the failures should be ignored (i.e. the key is prefixed with `-`).
+    ignore_failure: bool,
+}
+
+impl Entry {
+    /// Returns the key as it is written.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Returns the normalized name of the parameter.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the value of the entry.
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Returns `true` if the failures should be ignored.
+    pub fn ignore_failure(&self) -> bool {
+        self.ignore_failure
+    }
+
+    /// Parses an entry from the given line.
+    fn parse(line: &str) -> Option<Self> {
+        let content = line.trim_end_matches(['\r', '\n']);
+        let trimmed_content = content.trim_start();
+        if trimmed_content.is_empty()
+            || trimmed_content.starts_with('#')
+            || trimmed_content.starts_with(';')
+        {
+            return None;
+        }
+        let (key_part, value_part) = content.split_once('=')?;
+        let key = key_part.trim();
+        let ignore_failure = key.starts_with('-');
+        let key = key.trim_start_matches('-').trim_start();
+        if key.is_empty() || key.starts_with('!') {
+            return None;
+        }
+        let value = value_part.trim();
+        let value_start = key_part.len() + 1 + (value_part.len() - value_part.trim_start().len());
+        let value_end = value_start + value.len();
+        Some(Self {
+            prefix: line[..value_start].to_string(),
+            key: key.to_string(),
+            name: Assignment::normalize_name(key),
+            value: value.to_string(),
+            suffix: line[value_end..].to_string(),
+            ignore_failure,
+        })
+    }
+}
+
+impl Display for Entry {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}{}{}", self.prefix, self.value, self.suffix)
+    }
+}
+
+/// Line of a sysctl.conf document.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Line {
+    /// Blank line, comment or an unrecognized line (kept as is).
+    Text(String),
+    /// Key-value entry.
+    Entry(Entry),
+}
+
+impl Display for Line {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Text(text) => write!(f, "{text}"),
+            Self::Entry(entry) => write!(f, "{entry}"),
+        }
+    }
+}
+
+/// sysctl.conf document that preserves the comments and layout.
+///
+/// Converting the document back to a string results in the same content
+/// unless it is modified.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Document {
+    /// Lines of the document (including the line endings).
+    pub lines: Vec<Line>,
+}
+
+impl Display for Document {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.lines.iter().try_for_each(|line| write!(f, "{line}"))
+    }
+}
+
+impl Document {
+    /// Parses the document from the given string.
+    pub fn parse(input: &str) -> Self {
+        Self {
+            lines: input
+                .split_inclusive('\n')
+                .map(|line| match Entry::parse(line) {
+                    Some(entry) => Line::Entry(entry),
+                    None => Line::Text(line.to_string()),
+                })
+                .collect(),
+        }
+    }
+
+    /// Reads the document from the given file.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(Self::parse(&reader::read_to_string(path)?))
+    }
+
+    /// Saves the document to the given file.
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let mut file = File::create(path)?;
+        file.write_all(self.to_string().as_bytes())?;
+        Ok(())
+    }
+
+    /// Returns the entries in the document.
+    pub fn entries(&self) -> impl Iterator<Item = &Entry> {
+        self.lines.iter().filter_map(|line| match line {
+            Line::Entry(entry) => Some(entry),
+            Line::Text(_) => None,
+        })
+    }
+
+    /// Returns the effective (i.e. last) entry of the given parameter.
+    pub fn get(&self, name: &str) -> Option<&Entry> {
+        let name = Assignment::normalize_name(name);
+        self.entries().filter(|entry| entry.name == name).last()
+    }
+
+    /// Returns the line ending that is used in the document.
+    fn get_line_ending(&self) -> &'static str {
+        match self.lines.first() {
+            Some(line) if line.to_string().ends_with("\r\n") => "\r\n",
+            _ => "\n",
+        }
+    }
+
+    /// Sets the value of the given parameter.
+    ///
+    /// Updates the effective entry in place or appends a new entry if it does not exist.
+    pub fn set(&mut self, name: &str, value: &str) {
+        let normalized_name = Assignment::normalize_name(name);
+        if let Some(entry) = self.lines.iter_mut().rev().find_map(|line| match line {
+            Line::Entry(entry) if entry.name == normalized_name => Some(entry),
+            _ => None,
+        }) {
+            entry.value = value.to_string();
+            return;
+        }
+        self.insert(name, value);
+    }
+
+    /// Appends a new entry to the end of the document.
+    pub fn insert(&mut self, name: &str, value: &str) {
+        let line_ending = self.get_line_ending();
+        if let Some(last_line) = self.lines.last_mut() {
+            match last_line {
+                Line::Text(text) if !text.ends_with('\n') => text.push_str(line_ending),
+                Line::Entry(entry) if !entry.suffix.ends_with('\n') => {
+                    entry.suffix.push_str(line_ending)
+                }
+                _ => {}
+            }
+        }
+        self.lines.push(Line::Entry(Entry {
+            prefix: format!("{name} = "),
+            key: name.to_string(),
+            name: Assignment::normalize_name(name),
+            value: value.to_string(),
+            suffix: line_ending.to_string(),
+            ignore_failure: false,
+        }));
+    }
+
+    /// Removes all the entries of the given parameter.
+    ///
+    /// Returns `true` if any entries are removed.
+    pub fn remove(&mut self, name: &str) -> bool {
+        let name = Assignment::normalize_name(name);
+        let len = self.lines.len();
+        self.lines
+            .retain(|line| !matches!(line, Line::Entry(entry) if entry.name == name));
+        len != self.lines.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_document() {
+        let input = "# sysctl.conf sample\n\
+                     ;kernel.domainname = commented.com\n\
+                     \n  kernel.domainname = example.com  \n\
+                     -net/ipv4/ip_forward=1\r\n\
+                     vm.dirty_ratio_foo = 1\n\
+                     invalid line\n\
+                     vm.dirty_ratio\t=\t20";
+        let mut document = Document::parse(input);
+        assert_eq!(input, document.to_string());
+        assert_eq!(4, document.entries().count());
+
+        let entry = document
+            .get("net.ipv4.ip_forward")
+            .expect("entry not found");
+        assert_eq!("net/ipv4/ip_forward", entry.key());
+        assert_eq!("1", entry.value());
+        assert!(entry.ignore_failure());
+        assert!(document.get("kernel/domainname").is_some());
+
+        document.set("vm.dirty_ratio", "10");
+        document.set("net.ipv4.ip_forward", "0");
+        document.set("kernel.domainname", "example.org");
+        document.set("vm.swappiness", "60");
+        assert!(document.remove("vm.dirty_ratio_foo"));
+        assert!(!document.remove("vm.unexisting"));
+        assert_eq!(
+            "# sysctl.conf sample\n\
+             ;kernel.domainname = commented.com\n\
+             \n  kernel.domainname = example.org  \n\
+             -net/ipv4/ip_forward=0\r\n\
+             invalid line\n\
+             vm.dirty_ratio\t=\t10\n\
+             vm.swappiness = 60\n",
+            document.to_string()
+        );
+
+        let mut document = Document::default();
+        document.set("vm.swappiness", "10");
+        assert_eq!("vm.swappiness = 10\n", document.to_string());
+    }
+}
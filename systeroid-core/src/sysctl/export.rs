@@ -0,0 +1,206 @@
+use crate::error::{Error, Result};
+use crate::sysctl::namespace::{self, Namespace};
+use std::fmt::{self, Display, Formatter};
+use std::io::Write;
+use std::str::FromStr;
+
+/// Format for exporting the values to a container runtime.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// `securityContext.sysctls` of a Kubernetes pod.
+    Kubernetes,
+    /// `--sysctl` arguments of `docker run`.
+    Docker,
+    /// `default_sysctls` in `containers.conf` of Podman.
+    Podman,
+    /// `lxc.sysctl.*` keys of a LXC container configuration.
+    Lxc,
+}
+
+impl FromStr for ExportFormat {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "kubernetes" | "k8s" => Ok(Self::Kubernetes),
+            "docker" => Ok(Self::Docker),
+            "podman" => Ok(Self::Podman),
+            "lxc" => Ok(Self::Lxc),
+            _ => Err(Error::ExportError(format!("unknown export format: {s:?}"))),
+        }
+    }
+}
+
+impl Display for ExportFormat {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Kubernetes => write!(f, "kubernetes"),
+            Self::Docker => write!(f, "docker"),
+            Self::Podman => write!(f, "podman"),
+            Self::Lxc => write!(f, "lxc"),
+        }
+    }
+}
+
+impl ExportFormat {
+    /// Returns `true` if the parameters in the given namespace can be set with the format.
+    ///
+    /// Only LXC allows setting the parameters of the UTS namespace since the others
+    /// have dedicated options for the hostname.
+    pub fn supports(&self, namespace: Namespace) -> bool {
+        match self {
+            Self::Lxc => namespace.is_namespaced(),
+            _ => matches!(namespace, Namespace::Net | Namespace::Ipc),
+        }
+    }
+}
+
+/// Quotes the given argument for the shell if it contains special characters.
+fn quote_shell(value: &str) -> String {
+    if value
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "-_.,:/=+@%".contains(c))
+    {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
+
+/// Prints the given values in the given format.
+///
+/// Parameters that cannot be set per container are skipped with a warning.
+pub fn export<Output: Write>(
+    format: ExportFormat,
+    values: &[(String, String)],
+    output: &mut Output,
+) -> Result<()> {
+    let values = values
+        .iter()
+        .filter(|(name, _)| {
+            let namespace = Namespace::from_name(name);
+            if !namespace.is_namespaced() {
+                log::warn!(target: "export", "{} is not namespaced, skipping", name);
+            } else if !format.supports(namespace) {
+                log::warn!(
+                    target: "export",
+                    "{} ({} namespace) is not supported by {}, skipping",
+                    name,
+                    namespace,
+                    format
+                );
+            }
+            format.supports(namespace)
+        })
+        .map(|(name, value)| {
+            (
+                name.as_str(),
+                value.split_whitespace().collect::<Vec<&str>>().join(" "),
+            )
+        })
+        .collect::<Vec<(&str, String)>>();
+    match format {
+        ExportFormat::Kubernetes => {
+            writeln!(output, "securityContext:")?;
+            if values.is_empty() {
+                writeln!(output, "  sysctls: []")?;
+            } else {
+                writeln!(output, "  sysctls:")?;
+            }
+            for (name, value) in values {
+                if !namespace::is_kubelet_safe(name) {
+                    writeln!(
+                        output,
+                        "    # unsafe: requires --allowed-unsafe-sysctls on the kubelet"
+                    )?;
+                }
+                writeln!(output, "    - name: {name}")?;
+                writeln!(output, "      value: {}", serde_json::to_string(&value)?)?;
+            }
+        }
+        ExportFormat::Docker => {
+            if !values.is_empty() {
+                writeln!(
+                    output,
+                    "{}",
+                    values
+                        .iter()
+                        .map(|(name, value)| format!(
+                            "--sysctl {}",
+                            quote_shell(&format!("{name}={value}"))
+                        ))
+                        .collect::<Vec<String>>()
+                        .join(" \\\n")
+                )?;
+            }
+        }
+        ExportFormat::Podman => {
+            writeln!(output, "[containers]")?;
+            writeln!(output, "default_sysctls = [")?;
+            for (name, value) in values {
+                writeln!(
+                    output,
+                    "  {},",
+                    serde_json::to_string(&format!("{name}={value}"))?
+                )?;
+            }
+            writeln!(output, "]")?;
+        }
+        ExportFormat::Lxc => {
+            for (name, value) in values {
+                writeln!(output, "lxc.sysctl.{name} = {value}")?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export() -> Result<()> {
+        let values = [
+            ("net.ipv4.tcp_syncookies", "1"),
+            ("net.core.somaxconn", "1024"),
+            ("net.ipv4.ip_local_port_range", "1024\t65535"),
+            ("kernel.hostname", "example"),
+            ("vm.swappiness", "10"),
+        ]
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect::<Vec<(String, String)>>();
+        let get_output = |format: ExportFormat| -> Result<String> {
+            let mut output = Vec::new();
+            export(format, &values, &mut output)?;
+            Ok(String::from_utf8_lossy(&output).to_string())
+        };
+        assert_eq!(
+            "securityContext:\n  sysctls:\n\
+            \x20   - name: net.ipv4.tcp_syncookies\n      value: \"1\"\n\
+            \x20   # unsafe: requires --allowed-unsafe-sysctls on the kubelet\n\
+            \x20   - name: net.core.somaxconn\n      value: \"1024\"\n\
+            \x20   - name: net.ipv4.ip_local_port_range\n      value: \"1024 65535\"\n",
+            get_output(ExportFormat::Kubernetes)?
+        );
+        assert_eq!(
+            "--sysctl net.ipv4.tcp_syncookies=1 \\\n--sysctl net.core.somaxconn=1024 \\\n\
+            --sysctl 'net.ipv4.ip_local_port_range=1024 65535'\n",
+            get_output(ExportFormat::Docker)?
+        );
+        assert_eq!(
+            "[containers]\ndefault_sysctls = [\n  \"net.ipv4.tcp_syncookies=1\",\n  \
+            \"net.core.somaxconn=1024\",\n  \"net.ipv4.ip_local_port_range=1024 65535\",\n]\n",
+            get_output(ExportFormat::Podman)?
+        );
+        assert_eq!(
+            "lxc.sysctl.net.ipv4.tcp_syncookies = 1\nlxc.sysctl.net.core.somaxconn = 1024\n\
+            lxc.sysctl.net.ipv4.ip_local_port_range = 1024 65535\n\
+            lxc.sysctl.kernel.hostname = example\n",
+            get_output(ExportFormat::Lxc)?
+        );
+        assert_eq!(ExportFormat::Kubernetes, ExportFormat::from_str("k8s")?);
+        assert!(ExportFormat::from_str("nspawn").is_err());
+        Ok(())
+    }
+}
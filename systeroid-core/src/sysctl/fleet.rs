@@ -0,0 +1,457 @@
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::sysctl::backend::{Backend, OfflineBackend};
+use crate::sysctl::source::ParameterValues;
+use owo_colors::{OwoColorize, Stream::Stdout};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+/// Parameters that differ on every host regardless of the configuration.
+pub const VOLATILE_PARAMS: &[&str] = &[
+    "fs.dentry-state",
+    "fs.file-nr",
+    "fs.inode-nr",
+    "fs.inode-state",
+    "kernel.hostname",
+    "kernel.ns_last_pid",
+    "kernel.random.boot_id",
+    "kernel.random.entropy_avail",
+    "kernel.random.uuid",
+];
+
+/// Parameter that is not identical across the hosts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FleetParameter {
+    /// Name of the parameter.
+    pub name: String,
+    /// Values of the parameter in the order of the hosts.
+    pub values: Vec<Option<String>>,
+    /// Value that is shared by more than half of the hosts.
+    pub majority: Option<String>,
+}
+
+impl FleetParameter {
+    /// Returns `true` if the value of the host at the given index differs from the majority.
+    pub fn is_outlier(&self, index: usize) -> bool {
+        self.majority.is_some() && self.values.get(index) != Some(&self.majority)
+    }
+}
+
+/// Parameter entry in the JSON output.
+#[derive(Serialize)]
+struct JsonFleetParameter<'a> {
+    /// Name of the parameter.
+    name: &'a str,
+    /// Values of the parameter for each host.
+    values: BTreeMap<&'a str, &'a Option<String>>,
+    /// Majority value.
+    majority: &'a Option<String>,
+    /// Hosts that differ from the majority.
+    outliers: Vec<&'a str>,
+}
+
+/// JSON output of the report.
+#[derive(Serialize)]
+struct JsonFleetReport<'a> {
+    /// Names of the hosts.
+    hosts: &'a [String],
+    /// Hosts with identical values.
+    groups: Vec<Vec<&'a str>>,
+    /// Parameters that differ.
+    parameters: Vec<JsonFleetParameter<'a>>,
+}
+
+/// Comparison of the parameters across many hosts.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FleetReport {
+    /// Names of the hosts.
+    pub hosts: Vec<String>,
+    /// Parameters that are not identical across the hosts.
+    pub parameters: Vec<FleetParameter>,
+    /// Indices of the hosts that have identical values, largest group first.
+    pub groups: Vec<Vec<usize>>,
+}
+
+/// Quotes the given field for CSV if it contains special characters.
+fn quote_csv(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Reads the dumps, snapshots or sosreports of the hosts in the given directory.
+///
+/// Hosts are named after the files (without the extension) and the files that cannot
+/// be read are skipped with a warning.
+pub fn read_hosts(dir: &Path) -> Result<Vec<(String, ParameterValues)>> {
+    let mut paths = fs::read_dir(dir)?
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| {
+            path.file_name()
+                .is_some_and(|name| !name.to_string_lossy().starts_with('.'))
+        })
+        .collect::<Vec<_>>();
+    paths.sort();
+    let mut hosts = Vec::new();
+    for path in paths {
+        let host = if path.is_dir() {
+            path.file_name()
+        } else {
+            path.file_stem()
+        }
+        .map(|v| v.to_string_lossy().to_string())
+        .unwrap_or_default();
+        match OfflineBackend::load(&path).and_then(|backend| backend.get_parameters()) {
+            Ok(parameters) => hosts.push((
+                host,
+                parameters
+                    .into_iter()
+                    .map(|parameter| (parameter.name, parameter.value.to_string()))
+                    .collect(),
+            )),
+            Err(e) => log::warn!(target: "fleet", "cannot read {:?}: {}", path, e),
+        }
+    }
+    if hosts.is_empty() {
+        return Err(Error::FleetError(format!(
+            "no dumps found in {}",
+            dir.display()
+        )));
+    }
+    Ok(hosts)
+}
+
+impl FleetReport {
+    /// Compares the values of the given hosts.
+    ///
+    /// Values are compared after normalizing the whitespace and the volatile parameters
+    /// (e.g. `kernel.random.boot_id`) are skipped unless `include_volatile` is set.
+    pub fn new(hosts: Vec<(String, ParameterValues)>, include_volatile: bool) -> Self {
+        let mut names = hosts
+            .iter()
+            .flat_map(|(_, values)| values.keys())
+            .filter(|name| include_volatile || !VOLATILE_PARAMS.contains(&name.as_str()))
+            .collect::<Vec<&String>>();
+        names.sort();
+        names.dedup();
+        let parameters = names
+            .into_iter()
+            .filter_map(|name| {
+                let values = hosts
+                    .iter()
+                    .map(|(_, values)| {
+                        values
+                            .get(name)
+                            .map(|v| v.split_whitespace().collect::<Vec<&str>>().join(" "))
+                    })
+                    .collect::<Vec<Option<String>>>();
+                if values.iter().all(|value| value == &values[0]) {
+                    return None;
+                }
+                let mut counts = Vec::<(&String, usize)>::new();
+                for value in values.iter().flatten() {
+                    match counts.iter_mut().find(|(v, _)| *v == value) {
+                        Some((_, count)) => *count += 1,
+                        None => counts.push((value, 1)),
+                    }
+                }
+                let majority = counts
+                    .into_iter()
+                    .find(|(_, count)| count * 2 > hosts.len())
+                    .map(|(value, _)| value.to_string());
+                Some(FleetParameter {
+                    name: name.to_string(),
+                    values,
+                    majority,
+                })
+            })
+            .collect::<Vec<FleetParameter>>();
+        let mut groups = Vec::<Vec<usize>>::new();
+        for index in 0..hosts.len() {
+            match groups.iter_mut().find(|group| {
+                parameters
+                    .iter()
+                    .all(|parameter| parameter.values[group[0]] == parameter.values[index])
+            }) {
+                Some(group) => group.push(index),
+                None => groups.push(vec![index]),
+            }
+        }
+        groups.sort_by_key(|group| std::cmp::Reverse(group.len()));
+        Self {
+            hosts: hosts.into_iter().map(|(host, _)| host).collect(),
+            parameters,
+            groups,
+        }
+    }
+
+    /// Returns the indices of the hosts that differ from the majority along with
+    /// the parameters.
+    pub fn get_outliers(&self) -> Vec<(usize, Vec<&FleetParameter>)> {
+        (0..self.hosts.len())
+            .filter_map(|index| {
+                let parameters = self
+                    .parameters
+                    .iter()
+                    .filter(|parameter| parameter.is_outlier(index))
+                    .collect::<Vec<&FleetParameter>>();
+                (!parameters.is_empty()).then_some((index, parameters))
+            })
+            .collect()
+    }
+
+    /// Prints the report as a table of the values for each group of hosts.
+    ///
+    /// Values that differ from the majority are highlighted.
+    pub fn display<Output: Write>(&self, config: &Config, output: &mut Output) -> Result<()> {
+        let name_width = self
+            .parameters
+            .iter()
+            .map(|parameter| parameter.name.len())
+            .chain([9])
+            .max()
+            .unwrap_or_default();
+        let get_value = |parameter: &FleetParameter, group: &[usize]| {
+            parameter.values[group[0]]
+                .clone()
+                .unwrap_or_else(|| String::from("-"))
+        };
+        let widths = self
+            .groups
+            .iter()
+            .enumerate()
+            .map(|(i, group)| {
+                self.parameters
+                    .iter()
+                    .map(|parameter| get_value(parameter, group).len())
+                    .chain([format!("#{}", i + 1).len()])
+                    .max()
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<usize>>();
+        if !self.parameters.is_empty() {
+            writeln!(
+                output,
+                "{}",
+                format!(
+                    "{:<name_width$} {}",
+                    "PARAMETER",
+                    widths
+                        .iter()
+                        .enumerate()
+                        .map(|(i, width)| format!("{:<width$}", format!("#{}", i + 1)))
+                        .collect::<Vec<String>>()
+                        .join(" ")
+                )
+                .trim_end()
+                .if_supports_color(Stdout, |v| v.color(config.cli.color.default_color))
+            )?;
+        }
+        for parameter in &self.parameters {
+            let values = self
+                .groups
+                .iter()
+                .zip(&widths)
+                .map(|(group, width)| {
+                    let value = format!("{:<width$}", get_value(parameter, group));
+                    if parameter.is_outlier(group[0]) {
+                        value.if_supports_color(Stdout, |v| v.red()).to_string()
+                    } else {
+                        value
+                    }
+                })
+                .collect::<Vec<String>>();
+            writeln!(
+                output,
+                "{:<name_width$} {}",
+                parameter.name,
+                values.join(" ").trim_end()
+            )?;
+        }
+        if !self.parameters.is_empty() {
+            writeln!(output)?;
+        }
+        for (i, group) in self.groups.iter().enumerate() {
+            writeln!(
+                output,
+                "{} ({} host{}): {}",
+                format!("#{}", i + 1).if_supports_color(Stdout, |v| v.bold()),
+                group.len(),
+                if group.len() == 1 { "" } else { "s" },
+                group
+                    .iter()
+                    .map(|index| self.hosts[*index].as_str())
+                    .collect::<Vec<&str>>()
+                    .join(", ")
+            )?;
+        }
+        let outliers = self.get_outliers();
+        if !outliers.is_empty() {
+            writeln!(output)?;
+        }
+        for (index, parameters) in &outliers {
+            writeln!(
+                output,
+                "{}:",
+                self.hosts[*index].if_supports_color(Stdout, |v| v.bold())
+            )?;
+            for parameter in parameters {
+                writeln!(
+                    output,
+                    "  {} = {} {}",
+                    parameter.name,
+                    parameter.values[*index]
+                        .as_deref()
+                        .unwrap_or("-")
+                        .if_supports_color(Stdout, |v| v.red()),
+                    format!(
+                        "(majority: {})",
+                        parameter.majority.as_deref().unwrap_or_default()
+                    )
+                    .if_supports_color(Stdout, |v| v.color(config.cli.color.default_color))
+                )?;
+            }
+        }
+        writeln!(
+            output,
+            "\n{} parameter(s) differ across {} host(s) in {} group(s), {} outlier(s)",
+            self.parameters.len(),
+            self.hosts.len(),
+            self.groups.len(),
+            outliers.len()
+        )?;
+        Ok(())
+    }
+
+    /// Prints the report in JSON format.
+    pub fn display_json<Output: Write>(&self, output: &mut Output) -> Result<()> {
+        let report = JsonFleetReport {
+            hosts: &self.hosts,
+            groups: self
+                .groups
+                .iter()
+                .map(|group| {
+                    group
+                        .iter()
+                        .map(|index| self.hosts[*index].as_str())
+                        .collect()
+                })
+                .collect(),
+            parameters: self
+                .parameters
+                .iter()
+                .map(|parameter| JsonFleetParameter {
+                    name: &parameter.name,
+                    values: self
+                        .hosts
+                        .iter()
+                        .map(String::as_str)
+                        .zip(&parameter.values)
+                        .collect(),
+                    majority: &parameter.majority,
+                    outliers: self
+                        .hosts
+                        .iter()
+                        .enumerate()
+                        .filter(|(index, _)| parameter.is_outlier(*index))
+                        .map(|(_, host)| host.as_str())
+                        .collect(),
+                })
+                .collect(),
+        };
+        writeln!(output, "{}", serde_json::to_string(&report)?)?;
+        Ok(())
+    }
+
+    /// Prints the host × value matrix in CSV format.
+    pub fn display_csv<Output: Write>(&self, output: &mut Output) -> Result<()> {
+        writeln!(
+            output,
+            "name,majority,{}",
+            self.hosts
+                .iter()
+                .map(|host| quote_csv(host))
+                .collect::<Vec<String>>()
+                .join(",")
+        )?;
+        for parameter in &self.parameters {
+            writeln!(
+                output,
+                "{},{},{}",
+                quote_csv(&parameter.name),
+                quote_csv(parameter.majority.as_deref().unwrap_or_default()),
+                parameter
+                    .values
+                    .iter()
+                    .map(|value| quote_csv(value.as_deref().unwrap_or_default()))
+                    .collect::<Vec<String>>()
+                    .join(",")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fleet_report() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let root = temp_dir.path().to_path_buf();
+        fs::create_dir_all(&root)?;
+        for (host, dump) in [
+            ("node-1", "vm.swappiness = 60\nnet.core.somaxconn = 4096\n"),
+            ("node-2", "vm.swappiness = 60\nnet.core.somaxconn = 4096\n"),
+            ("node-3", "vm.swappiness = 10\nnet.core.somaxconn = 4096\n"),
+            ("node-4", "vm.swappiness = 60\n"),
+        ] {
+            fs::write(
+                root.join(format!("{host}.txt")),
+                format!("{dump}kernel.random.boot_id = {host}\n"),
+            )?;
+        }
+        let report = FleetReport::new(read_hosts(&root)?, false);
+        assert_eq!(vec!["node-1", "node-2", "node-3", "node-4"], report.hosts);
+        assert_eq!(
+            vec!["net.core.somaxconn", "vm.swappiness"],
+            report
+                .parameters
+                .iter()
+                .map(|p| p.name.as_str())
+                .collect::<Vec<&str>>()
+        );
+        assert_eq!(Some(String::from("60")), report.parameters[1].majority);
+        assert_eq!(vec![vec![0, 1], vec![2], vec![3]], report.groups);
+        assert_eq!(
+            vec![("node-3", 1), ("node-4", 1)],
+            report
+                .get_outliers()
+                .iter()
+                .map(|(index, parameters)| (report.hosts[*index].as_str(), parameters.len()))
+                .collect::<Vec<(&str, usize)>>()
+        );
+
+        let mut output = Vec::new();
+        report.display_csv(&mut output)?;
+        assert_eq!(
+            "name,majority,node-1,node-2,node-3,node-4\n\
+            net.core.somaxconn,4096,4096,4096,4096,\n\
+            vm.swappiness,60,60,60,10,60\n",
+            String::from_utf8_lossy(&output)
+        );
+        assert_eq!("\"a,\"\"b\"\"\"", quote_csv("a,\"b\""));
+
+        let report = FleetReport::new(read_hosts(&root)?, true);
+        assert_eq!("kernel.random.boot_id", report.parameters[0].name);
+        assert_eq!(None, report.parameters[0].majority);
+        assert_eq!(4, report.groups.len());
+        Ok(())
+    }
+}
@@ -0,0 +1,247 @@
+use crate::config::Config;
+use crate::error::Result;
+use chrono::{Local, TimeZone};
+use owo_colors::{OwoColorize, Stream::Stdout};
+use parseit::reader;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fmt::{self, Display, Formatter};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Source of a change.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeSource {
+    /// Command line.
+    Cli,
+    /// File (e.g. preload file or snapshot).
+    File(PathBuf),
+    /// Terminal user interface.
+    Tui,
+    /// Undo of a previous change.
+    Undo,
+}
+
+impl Display for ChangeSource {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Cli => write!(f, "cli"),
+            Self::File(path) => write!(f, "{}", path.display()),
+            Self::Tui => write!(f, "tui"),
+            Self::Undo => write!(f, "undo"),
+        }
+    }
+}
+
+/// Entry of the change journal.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// Date of the change as UNIX timestamp.
+    pub timestamp: u64,
+    /// User that made the change.
+    pub user: String,
+    /// Name of the kernel parameter.
+    pub name: String,
+    /// Value before the change.
+    pub old_value: String,
+    /// Value after the change.
+    pub new_value: String,
+    /// Source of the change.
+    pub source: ChangeSource,
+}
+
+impl JournalEntry {
+    /// Constructs a new instance for a change that is made now by the current user.
+    pub fn new(name: &str, old_value: &str, new_value: &str, source: ChangeSource) -> Result<Self> {
+        Ok(Self {
+            timestamp: SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)?
+                .as_secs(),
+            user: ["SUDO_USER", "USER", "LOGNAME"]
+                .iter()
+                .find_map(|name| env::var(name).ok())
+                .unwrap_or_else(|| String::from("unknown")),
+            name: name.to_string(),
+            old_value: old_value.to_string(),
+            new_value: new_value.to_string(),
+            source,
+        })
+    }
+
+    /// Prints the entry to the given output.
+    pub fn display<Output: Write>(&self, config: &Config, output: &mut Output) -> Result<()> {
+        let date = Local
+            .timestamp_opt(self.timestamp as i64, 0)
+            .single()
+            .map(|date| date.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(|| self.timestamp.to_string());
+        writeln!(
+            output,
+            "{} {} {} {} {} {} -> {}",
+            date.if_supports_color(Stdout, |v| v.color(config.cli.color.default_color)),
+            self.user,
+            format!("({})", self.source)
+                .if_supports_color(Stdout, |v| v.color(config.cli.color.default_color)),
+            self.name,
+            "=".if_supports_color(Stdout, |v| v.color(config.cli.color.default_color)),
+            self.old_value.replace('\n', " "),
+            self.new_value
+                .replace('\n', " ")
+                .if_supports_color(Stdout, |v| v.bold()),
+        )?;
+        Ok(())
+    }
+
+    /// Prints the given entries in JSON format.
+    pub fn display_bulk_json<Output: Write>(entries: &[Self], output: &mut Output) -> Result<()> {
+        writeln!(output, "{}", serde_json::to_string(entries)?)?;
+        Ok(())
+    }
+}
+
+/// Journal of the changes that are made to the kernel parameters.
+///
+/// Entries are stored in JSON lines format.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Journal {
+    /// Path of the journal file.
+    path: PathBuf,
+}
+
+impl Journal {
+    /// Constructs a new instance.
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Returns the default path of the journal in the state directory of the user.
+    ///
+    /// `$XDG_STATE_HOME` is used if it is set, `$HOME/.local/state` otherwise.
+    pub fn get_default_path() -> Option<PathBuf> {
+        env::var_os("XDG_STATE_HOME")
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute())
+            .or_else(|| dirs_next::home_dir().map(|path| path.join(".local").join("state")))
+            .map(|path| path.join(env!("CARGO_PKG_NAME")).join("journal.jsonl"))
+    }
+
+    /// Records the given change in the journal that is set in the configuration.
+    ///
+    /// Failures are logged since they should not prevent the change.
+    pub fn record(
+        config: &Config,
+        name: &str,
+        old_value: &str,
+        new_value: &str,
+        source: ChangeSource,
+    ) {
+        let Some(path) = &config.journal_path else {
+            return;
+        };
+        if let Err(e) = JournalEntry::new(name, old_value, new_value, source)
+            .and_then(|entry| Self::new(path).append(&entry))
+        {
+            log::warn!(target: "journal", "cannot record the change of {}: {}", name, e);
+        }
+    }
+
+    /// Appends the given entry to the journal.
+    pub fn append(&self, entry: &JournalEntry) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(entry)?)?;
+        Ok(())
+    }
+
+    /// Reads the entries in the journal.
+    pub fn read(&self) -> Result<Vec<JournalEntry>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        reader::read_to_string(&self.path)?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| Ok(serde_json::from_str(line)?))
+            .collect()
+    }
+
+    /// Returns the last changes that are not undone yet, starting from the most recent one.
+    ///
+    /// Each [`ChangeSource::Undo`] entry cancels the most recent change before it.
+    pub fn get_undoable(&self, count: usize) -> Result<Vec<JournalEntry>> {
+        let mut undone = 0;
+        let mut entries = Vec::new();
+        for entry in self.read()?.into_iter().rev() {
+            if entries.len() == count {
+                break;
+            }
+            if entry.source == ChangeSource::Undo {
+                undone += 1;
+            } else if undone != 0 {
+                undone -= 1;
+            } else {
+                entries.push(entry);
+            }
+        }
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_journal() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let path = temp_dir.path().join("journal").join("journal.jsonl");
+        let journal = Journal::new(&path);
+        assert!(journal.read()?.is_empty());
+        for (name, old_value, new_value, source) in [
+            ("vm.swappiness", "60", "10", ChangeSource::Cli),
+            (
+                "kernel.hostname",
+                "localhost",
+                "example",
+                ChangeSource::File(PathBuf::from("/etc/sysctl.conf")),
+            ),
+            ("vm.swappiness", "10", "20", ChangeSource::Tui),
+            ("vm.swappiness", "20", "10", ChangeSource::Undo),
+        ] {
+            journal.append(&JournalEntry::new(name, old_value, new_value, source)?)?;
+        }
+        let entries = journal.read()?;
+        assert_eq!(4, entries.len());
+        assert_eq!(
+            ChangeSource::File(PathBuf::from("/etc/sysctl.conf")),
+            entries[1].source
+        );
+        assert_eq!(
+            vec!["localhost", "60"],
+            journal
+                .get_undoable(5)?
+                .iter()
+                .map(|entry| entry.old_value.as_str())
+                .collect::<Vec<&str>>()
+        );
+
+        let mut output = Vec::new();
+        JournalEntry {
+            timestamp: 0,
+            ..entries[0].clone()
+        }
+        .display(&Config::default(), &mut output)?;
+        assert!(String::from_utf8_lossy(&output).ends_with(" (cli) vm.swappiness = 60 -> 10\n"));
+        Ok(())
+    }
+}
@@ -0,0 +1,118 @@
+use crate::error::Result;
+use crate::sysctl::parameter::Parameter;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+/// Name of the metric.
+const METRIC_NAME: &str = "systeroid_param";
+
+/// Content type of the Prometheus text exposition format.
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Escapes the special characters in a label value.
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Prints the numeric values of the given parameters in Prometheus text exposition format.
+///
+/// Each value of a parameter that has multiple values (e.g. `net.ipv4.tcp_rmem`)
+/// is labeled with its index.
+pub fn write_metrics<'a, I, Output>(parameters: I, output: &mut Output) -> Result<()>
+where
+    I: Iterator<Item = &'a Parameter>,
+    Output: Write,
+{
+    writeln!(
+        output,
+        "# HELP {METRIC_NAME} Value of the kernel parameter."
+    )?;
+    writeln!(output, "# TYPE {METRIC_NAME} gauge")?;
+    for parameter in parameters {
+        let Some(values) = parameter.value.as_integers() else {
+            continue;
+        };
+        let name = escape_label(&parameter.name);
+        let section = escape_label(parameter.name.split('.').next().unwrap_or_default());
+        match values.as_slice() {
+            [value] => writeln!(
+                output,
+                "{METRIC_NAME}{{name=\"{name}\",section=\"{section}\"}} {value}"
+            )?,
+            values => {
+                for (index, value) in values.iter().enumerate() {
+                    writeln!(
+                        output,
+                        "{METRIC_NAME}{{name=\"{name}\",section=\"{section}\",index=\"{index}\"}} {value}"
+                    )?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Saves the metrics of the given parameters to a file for the textfile collector
+/// of the node exporter.
+///
+/// The file is replaced atomically so that the collector never reads a partial file.
+pub fn save_metrics<'a, I>(parameters: I, path: &Path) -> Result<()>
+where
+    I: Iterator<Item = &'a Parameter>,
+{
+    let mut metrics = Vec::new();
+    write_metrics(parameters, &mut metrics)?;
+    let temp_path = path.with_extension(format!("prom.{}.tmp", std::process::id()));
+    fs::write(&temp_path, metrics)?;
+    fs::rename(temp_path, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::sysctl::backend::DirectoryBackend;
+    use crate::sysctl::controller::Sysctl;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_metrics() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let root = temp_dir.path().to_path_buf();
+        fs::create_dir_all(root.join("vm"))?;
+        fs::create_dir_all(root.join("kernel"))?;
+        fs::create_dir_all(root.join("net").join("ipv4"))?;
+        fs::write(root.join("vm").join("swappiness"), "60\n")?;
+        fs::write(root.join("kernel").join("hostname"), "localhost\n")?;
+        fs::write(
+            root.join("net").join("ipv4").join("tcp_rmem"),
+            "4096\t131072\t6291456\n",
+        )?;
+        let sysctl =
+            Sysctl::init_with_backend(Config::default(), Arc::new(DirectoryBackend::new(&root)))?;
+
+        let expected = "# HELP systeroid_param Value of the kernel parameter.\n\
+            # TYPE systeroid_param gauge\n\
+            systeroid_param{name=\"net.ipv4.tcp_rmem\",section=\"net\",index=\"0\"} 4096\n\
+            systeroid_param{name=\"net.ipv4.tcp_rmem\",section=\"net\",index=\"1\"} 131072\n\
+            systeroid_param{name=\"net.ipv4.tcp_rmem\",section=\"net\",index=\"2\"} 6291456\n\
+            systeroid_param{name=\"vm.swappiness\",section=\"vm\"} 60\n";
+        let mut parameters = sysctl.parameters.clone();
+        parameters.sort_by(|a, b| a.name.cmp(&b.name));
+        let mut output = Vec::new();
+        write_metrics(parameters.iter(), &mut output)?;
+        assert_eq!(expected, String::from_utf8_lossy(&output));
+
+        let path = root.join("systeroid.prom");
+        save_metrics(parameters.iter(), &path)?;
+        assert_eq!(expected, fs::read_to_string(&path)?);
+        assert_eq!(4, fs::read_dir(&root)?.count());
+        assert_eq!("a\\\\b\\\"c\\n", escape_label("a\\b\"c\n"));
+        Ok(())
+    }
+}
@@ -10,12 +10,79 @@ pub mod r#type;
 /// Kernel parameter.
 pub mod parameter;
 
+/// Access permissions.
+pub mod access;
+
+/// Parameter backends.
+pub mod backend;
+
+/// Value validation.
+pub mod validation;
+
+/// Parameter snapshots.
+pub mod snapshot;
+
+/// Sources of parameter values.
+pub mod source;
+
+/// Differences between parameter sources.
+pub mod diff;
+
+/// Preload files.
+pub mod preload;
+
+/// sysctl.conf documents.
+pub mod document;
+
+/// Origins of parameter values.
+pub mod origin;
+
+/// Transactions for rolling back the changes.
+pub mod transaction;
+
+/// Planned changes.
+pub mod plan;
+
+/// Change journal.
+pub mod journal;
+
+/// Watching the changes.
+pub mod watch;
+
+/// Security audit.
+pub mod audit;
+/// Exporting to the container runtimes.
+pub mod export;
+/// Fleet comparison reports.
+pub mod fleet;
+/// Prometheus metrics.
+pub mod metrics;
+/// Kernel namespaces.
+pub mod namespace;
+/// Tuning profiles.
+pub mod profile;
+/// Query language for filtering the parameters.
+pub mod query;
+
 /// Path of the kernel parameters.
 pub(crate) const PROC_PATH: &str = "/proc/sys/";
 
 /// Default configuration file to preload values from.
 pub const DEFAULT_PRELOAD: &str = "/etc/sysctl.conf";
 
+/// Path of the kernel command line.
+pub const KERNEL_CMDLINE_PATH: &str = "/proc/cmdline";
+
+/// Default file to save the values to from the command line.
+pub const DEFAULT_SAVE_PATH: &str = "/etc/sysctl.d/90-systeroid.conf";
+
+/// Header of the files that are created for saving the values.
+pub(crate) const SAVE_FILE_HEADER: &str = "\
+# This file is managed by systeroid.
+# Values are saved via `systeroid --save` and `systeroid-tui`.
+
+";
+
 /// Default system configuration files to preload values from.
 pub const SYSTEM_PRELOAD: &[&str] = &[
     "/etc/sysctl.d",
@@ -0,0 +1,267 @@
+use crate::error::{Error, Result};
+use crate::sysctl::backend::{Backend, KernelBackend};
+use crate::sysctl::source::ParameterValues;
+use serde::Serialize;
+use std::fmt::{self, Display, Formatter};
+use std::fs::File;
+use std::io;
+use std::os::fd::AsRawFd;
+use std::path::PathBuf;
+use std::thread;
+
+/// Directory of the named network namespaces (e.g. created by `ip netns add`).
+pub const NETNS_DIR: &str = "/run/netns";
+
+/// Parameters of the IPC namespace.
+const IPC_PARAMS: &[&str] = &[
+    "kernel.msgmax",
+    "kernel.msgmnb",
+    "kernel.msgmni",
+    "kernel.msg_next_id",
+    "kernel.sem",
+    "kernel.sem_next_id",
+    "kernel.shmall",
+    "kernel.shmmax",
+    "kernel.shmmni",
+    "kernel.shm_next_id",
+    "kernel.shm_rmid_forced",
+];
+
+/// Parameters of the UTS namespace.
+const UTS_PARAMS: &[&str] = &["kernel.domainname", "kernel.hostname"];
+
+/// Parameters in the `net` section that are not namespaced.
+///
+/// `net.core.somaxconn` (since Linux 4.1) and `net.ipv4.tcp_rmem`/`tcp_wmem`
+/// (since Linux 4.15) are namespaced and therefore not listed.
+const GLOBAL_NET_PARAMS: &[&str] = &[
+    "net.core.bpf_jit_enable",
+    "net.core.bpf_jit_harden",
+    "net.core.bpf_jit_kallsyms",
+    "net.core.bpf_jit_limit",
+    "net.core.busy_poll",
+    "net.core.busy_read",
+    "net.core.default_qdisc",
+    "net.core.dev_weight",
+    "net.core.dev_weight_rx_bias",
+    "net.core.dev_weight_tx_bias",
+    "net.core.devconf_inherit_init_net",
+    "net.core.fb_tunnels_only_for_init_net",
+    "net.core.flow_limit_cpu_bitmap",
+    "net.core.flow_limit_table_len",
+    "net.core.gro_normal_batch",
+    "net.core.high_order_alloc_disable",
+    "net.core.max_skb_frags",
+    "net.core.mem_pcpu_rsv",
+    "net.core.message_burst",
+    "net.core.message_cost",
+    "net.core.netdev_budget",
+    "net.core.netdev_budget_usecs",
+    "net.core.netdev_max_backlog",
+    "net.core.netdev_rss_key",
+    "net.core.netdev_tstamp_prequeue",
+    "net.core.netdev_unregister_timeout_secs",
+    "net.core.rmem_default",
+    "net.core.rmem_max",
+    "net.core.rps_sock_flow_entries",
+    "net.core.skb_defer_max",
+    "net.core.tstamp_allow_data",
+    "net.core.warnings",
+    "net.core.wmem_default",
+    "net.core.wmem_max",
+    "net.ipv4.cipso_cache_bucket_size",
+    "net.ipv4.cipso_cache_enable",
+    "net.ipv4.cipso_rbm_optfmt",
+    "net.ipv4.cipso_rbm_strictvalid",
+    "net.ipv4.fib_sync_mem",
+    "net.ipv4.inet_peer_maxttl",
+    "net.ipv4.inet_peer_minttl",
+    "net.ipv4.inet_peer_threshold",
+    "net.ipv4.tcp_available_ulp",
+    "net.ipv4.tcp_max_orphans",
+    "net.ipv4.tcp_mem",
+    "net.ipv4.udp_mem",
+    "net.netfilter.nf_conntrack_buckets",
+    "net.netfilter.nf_conntrack_max",
+    "net.nf_conntrack_max",
+];
+
+/// Parameters that are considered safe by the kubelet.
+///
+/// They can be set for a pod without enabling them via `--allowed-unsafe-sysctls`.
+pub const KUBELET_SAFE_PARAMS: &[&str] = &[
+    "kernel.shm_rmid_forced",
+    "net.ipv4.ip_local_port_range",
+    "net.ipv4.ip_local_reserved_ports",
+    "net.ipv4.ip_unprivileged_port_start",
+    "net.ipv4.ping_group_range",
+    "net.ipv4.tcp_fin_timeout",
+    "net.ipv4.tcp_keepalive_intvl",
+    "net.ipv4.tcp_keepalive_probes",
+    "net.ipv4.tcp_keepalive_time",
+    "net.ipv4.tcp_rmem",
+    "net.ipv4.tcp_syncookies",
+    "net.ipv4.tcp_wmem",
+];
+
+/// Kernel namespace that a parameter belongs to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Namespace {
+    /// Network namespace.
+    Net,
+    /// IPC namespace.
+    Ipc,
+    /// UTS namespace.
+    Uts,
+    /// Not namespaced, i.e. shared with the host.
+    Global,
+}
+
+impl Display for Namespace {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Net => write!(f, "net"),
+            Self::Ipc => write!(f, "ipc"),
+            Self::Uts => write!(f, "uts"),
+            Self::Global => write!(f, "global"),
+        }
+    }
+}
+
+impl Namespace {
+    /// Returns the namespace of the given parameter.
+    pub fn from_name(name: &str) -> Self {
+        if name.starts_with("net.") && !GLOBAL_NET_PARAMS.contains(&name) {
+            Self::Net
+        } else if IPC_PARAMS.contains(&name) || name.starts_with("fs.mqueue.") {
+            Self::Ipc
+        } else if UTS_PARAMS.contains(&name) {
+            Self::Uts
+        } else {
+            Self::Global
+        }
+    }
+
+    /// Returns `true` if the parameters can be set per container.
+    pub fn is_namespaced(&self) -> bool {
+        *self != Self::Global
+    }
+}
+
+/// Returns `true` if the given parameter is considered safe by the kubelet.
+pub fn is_kubelet_safe(name: &str) -> bool {
+    KUBELET_SAFE_PARAMS.contains(&name)
+}
+
+/// Namespaces to read and write the parameters from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NamespaceTarget {
+    /// Named network namespace in [`NETNS_DIR`] or a path to a namespace file.
+    Netns(String),
+    /// Network, IPC and UTS namespaces of a process.
+    Pid(u32),
+}
+
+impl Display for NamespaceTarget {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Netns(name) => write!(f, "netns {name}"),
+            Self::Pid(pid) => write!(f, "pid {pid}"),
+        }
+    }
+}
+
+impl NamespaceTarget {
+    /// Returns the files of the namespaces along with their types.
+    fn get_files(&self) -> Vec<(PathBuf, libc::c_int)> {
+        match self {
+            Self::Netns(name) if name.contains('/') => {
+                vec![(PathBuf::from(name), libc::CLONE_NEWNET)]
+            }
+            Self::Netns(name) => vec![(PathBuf::from(NETNS_DIR).join(name), libc::CLONE_NEWNET)],
+            Self::Pid(pid) => {
+                let dir = PathBuf::from("/proc").join(pid.to_string()).join("ns");
+                vec![
+                    (dir.join("net"), libc::CLONE_NEWNET),
+                    (dir.join("ipc"), libc::CLONE_NEWIPC),
+                    (dir.join("uts"), libc::CLONE_NEWUTS),
+                ]
+            }
+        }
+    }
+
+    /// Enters the namespaces in the current thread.
+    ///
+    /// The threads that are spawned afterwards inherit the namespaces, so this should be
+    /// called before reading any parameters.
+    pub fn enter(&self) -> Result<()> {
+        for (path, namespace_type) in self.get_files() {
+            let file = File::open(&path).map_err(|e| {
+                Error::NamespaceError(format!("cannot open {}: {}", path.display(), e))
+            })?;
+            // SAFETY: the file descriptor is valid as long as the file is open.
+            if unsafe { libc::setns(file.as_raw_fd(), namespace_type) } != 0 {
+                return Err(Error::NamespaceError(format!(
+                    "cannot enter {}: {}",
+                    path.display(),
+                    io::Error::last_os_error()
+                )));
+            }
+        }
+        log::trace!(target: "namespace", "Entered {}", self);
+        Ok(())
+    }
+
+    /// Reads the values of the namespaced parameters in a separate thread.
+    ///
+    /// The namespaces of the current thread are not changed.
+    pub fn read_values(&self) -> Result<ParameterValues> {
+        let target = self.clone();
+        thread::spawn(move || -> Result<ParameterValues> {
+            target.enter()?;
+            Ok(KernelBackend
+                .get_parameters()?
+                .into_iter()
+                .filter(|parameter| parameter.get_namespace().is_namespaced())
+                .map(|parameter| (parameter.name, parameter.value.to_string()))
+                .collect())
+        })
+        .join()
+        .map_err(|_| Error::NamespaceError(format!("cannot read the values in {self}")))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_namespace() {
+        for (name, namespace) in [
+            ("net.ipv4.tcp_syncookies", Namespace::Net),
+            ("net.core.somaxconn", Namespace::Net),
+            ("net.core.rmem_max", Namespace::Global),
+            ("net.ipv4.tcp_mem", Namespace::Global),
+            ("net.ipv4.udp_mem", Namespace::Global),
+            ("net.ipv4.tcp_rmem", Namespace::Net),
+            ("kernel.shmmax", Namespace::Ipc),
+            ("fs.mqueue.msg_max", Namespace::Ipc),
+            ("kernel.hostname", Namespace::Uts),
+            ("vm.swappiness", Namespace::Global),
+        ] {
+            assert_eq!(namespace, Namespace::from_name(name));
+        }
+        assert!(!Namespace::Global.is_namespaced());
+        assert!(is_kubelet_safe("net.ipv4.ip_local_port_range"));
+        assert!(!is_kubelet_safe("net.core.somaxconn"));
+        assert_eq!(
+            vec![(PathBuf::from("/run/netns/blue"), libc::CLONE_NEWNET)],
+            NamespaceTarget::Netns(String::from("blue")).get_files()
+        );
+        assert_eq!(3, NamespaceTarget::Pid(1).get_files().len());
+        assert!(NamespaceTarget::Netns(String::from("systeroid-unknown"))
+            .enter()
+            .is_err());
+    }
+}
@@ -0,0 +1,288 @@
+use crate::config::Config;
+use crate::error::Result;
+use crate::sysctl::preload::{Assignment, Override};
+use owo_colors::{OwoColorize, Stream::Stdout};
+use serde::Serialize;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Prefix of the kernel parameters on the kernel command line.
+const CMDLINE_PREFIX: &str = "sysctl.";
+
+/// Parses the assignments on the given kernel command line.
+///
+/// Only the `sysctl.*` options are taken into account and both `.` and `/`
+/// are accepted as separators.
+pub fn parse_cmdline(cmdline: &str, path: &Path) -> Vec<Assignment> {
+    let mut options = Vec::new();
+    let mut option = String::new();
+    let mut in_quotes = false;
+    for c in cmdline.chars() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            c if c.is_whitespace() && !in_quotes => {
+                if !option.is_empty() {
+                    options.push(option.clone());
+                    option.clear();
+                }
+            }
+            c => option.push(c),
+        }
+    }
+    if !option.is_empty() {
+        options.push(option);
+    }
+    options
+        .iter()
+        .filter_map(|option| {
+            let (name, value) = option.strip_prefix(CMDLINE_PREFIX)?.split_once('=')?;
+            Some(Assignment {
+                name: Assignment::normalize_name(name),
+                value: Some(value.to_string()),
+                path: path.to_path_buf(),
+                line: 1,
+                ignore_failure: false,
+            })
+        })
+        .collect()
+}
+
+/// Origin of a kernel parameter value.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Origin {
+    /// Assignment of the parameter.
+    #[serde(flatten)]
+    pub assignment: Assignment,
+    /// Whether if the assignment is the one that is applied at last (i.e. it wins at boot).
+    pub effective: bool,
+    /// Reason of the assignment not being applied if its file is shadowed or masked.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub overridden: Option<Override>,
+}
+
+impl Origin {
+    /// Returns the assignments of the given parameter in the order that they are applied.
+    ///
+    /// Kernel command line assignments are applied by the kernel before the files.
+    /// In the files, explicit assignments take precedence over the glob patterns and
+    /// exclusions prevent the glob patterns from being applied.
+    ///
+    /// The assignments in the overridden files are listed at last since they are not applied.
+    pub fn find<P: AsRef<Path>>(
+        cmdline_assignments: &[Assignment],
+        files: &[P],
+        overridden_files: &[(PathBuf, Override)],
+        name: &str,
+    ) -> Vec<Self> {
+        let name = Assignment::normalize_name(name);
+        let mut assignments = cmdline_assignments
+            .iter()
+            .filter(|assignment| assignment.name == name)
+            .cloned()
+            .collect::<Vec<Assignment>>();
+        let cmdline_count = assignments.len();
+        for file in files {
+            assignments.extend(
+                Self::parse_file(file.as_ref())
+                    .into_iter()
+                    .filter(|assignment| assignment.matches(&name)),
+            );
+        }
+        let file_assignments = &assignments[cmdline_count..];
+        let effective_index = file_assignments
+            .iter()
+            .rposition(|assignment| !assignment.is_glob() && assignment.value.is_some())
+            .or_else(|| {
+                if file_assignments
+                    .iter()
+                    .any(|assignment| !assignment.is_glob())
+                {
+                    None
+                } else {
+                    file_assignments.len().checked_sub(1)
+                }
+            })
+            .map(|index| index + cmdline_count)
+            .or_else(|| cmdline_count.checked_sub(1));
+        let overridden_origins = overridden_files.iter().flat_map(|(file, reason)| {
+            Self::parse_file(file)
+                .into_iter()
+                .filter(|assignment| assignment.matches(&name))
+                .map(|assignment| Self {
+                    assignment,
+                    effective: false,
+                    overridden: Some(reason.clone()),
+                })
+        });
+        assignments
+            .into_iter()
+            .enumerate()
+            .map(|(index, assignment)| Self {
+                assignment,
+                effective: Some(index) == effective_index,
+                overridden: None,
+            })
+            .chain(overridden_origins)
+            .collect()
+    }
+
+    /// Parses the assignments in the given file and logs the error if it cannot be read.
+    fn parse_file(file: &Path) -> Vec<Assignment> {
+        Assignment::parse_file(file).unwrap_or_else(|e| {
+            log::error!(target: "sysctl", "cannot read {:?}: {}", file, e);
+            Vec::new()
+        })
+    }
+
+    /// Prints the origin to the given output.
+    pub fn display<Output: Write>(&self, config: &Config, output: &mut Output) -> Result<()> {
+        let location = format!(
+            "{}:{}:",
+            self.assignment.path.display(),
+            self.assignment.line
+        );
+        match &self.assignment.value {
+            Some(value) => write!(
+                output,
+                "{} {} {} {} {}",
+                if self.effective { "*" } else { " " },
+                location.if_supports_color(Stdout, |v| v.color(config.cli.color.default_color)),
+                self.assignment.name,
+                "=".if_supports_color(Stdout, |v| v.color(config.cli.color.default_color)),
+                if self.effective {
+                    value.if_supports_color(Stdout, |v| v.bold()).to_string()
+                } else {
+                    value.to_string()
+                }
+            )?,
+            None => write!(
+                output,
+                "  {} !{}",
+                location.if_supports_color(Stdout, |v| v.color(config.cli.color.default_color)),
+                self.assignment.name,
+            )?,
+        }
+        if let Some(reason) = &self.overridden {
+            write!(
+                output,
+                " ({})",
+                reason.if_supports_color(Stdout, |v| v.color(config.cli.color.default_color))
+            )?;
+        }
+        writeln!(output)?;
+        Ok(())
+    }
+
+    /// Prints the given origins in JSON format.
+    pub fn display_bulk_json<Output: Write>(origins: &[Self], output: &mut Output) -> Result<()> {
+        writeln!(output, "{}", serde_json::to_string(origins)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_origin() -> Result<()> {
+        let cmdline_path = PathBuf::from("/proc/cmdline");
+        let cmdline_assignments = parse_cmdline(
+            "BOOT_IMAGE=/vmlinuz quiet sysctl.vm.swappiness=30 \
+            sysctl.kernel/domainname=\"example com\" sysctl.vm.swappiness=40\n",
+            &cmdline_path,
+        );
+        assert_eq!(
+            vec![
+                ("vm.swappiness", "30"),
+                ("kernel.domainname", "example com"),
+                ("vm.swappiness", "40")
+            ],
+            cmdline_assignments
+                .iter()
+                .map(|v| (v.name.as_str(), v.value.as_deref().unwrap_or_default()))
+                .collect::<Vec<(&str, &str)>>()
+        );
+
+        let temp_dir = tempfile::tempdir()?;
+        let root = temp_dir.path().to_path_buf();
+        fs::create_dir_all(&root)?;
+        let files = vec![root.join("10-a.conf"), root.join("20-b.conf")];
+        fs::write(
+            &files[0],
+            "vm.swappiness = 10\nnet.ipv4.conf.*.rp_filter = 2\n",
+        )?;
+        fs::write(
+            &files[1],
+            "vm/swappiness = 20\n!net.ipv4.conf.lo.rp_filter\nvm.* = 1\n",
+        )?;
+
+        let origins = Origin::find(&cmdline_assignments, &files, &[], "vm.swappiness");
+        assert_eq!(
+            vec![
+                (cmdline_path.clone(), 1, false),
+                (cmdline_path.clone(), 1, false),
+                (files[0].clone(), 1, false),
+                (files[1].clone(), 1, true),
+                (files[1].clone(), 3, false),
+            ],
+            origins
+                .iter()
+                .map(|v| (v.assignment.path.clone(), v.assignment.line, v.effective))
+                .collect::<Vec<(PathBuf, usize, bool)>>()
+        );
+
+        let origins = Origin::find(
+            &cmdline_assignments,
+            &files,
+            &[],
+            "net.ipv4.conf.lo.rp_filter",
+        );
+        assert_eq!(2, origins.len());
+        assert!(origins.iter().all(|v| !v.effective));
+        let origins = Origin::find(
+            &cmdline_assignments,
+            &files,
+            &[],
+            "net.ipv4.conf.all.rp_filter",
+        );
+        assert!(origins[0].effective);
+        let origins = Origin::find(&cmdline_assignments, &files, &[], "kernel.domainname");
+        assert!(origins[0].effective);
+
+        let mut output = Vec::new();
+        origins[0].display(&Config::default(), &mut output)?;
+        assert_eq!(
+            "* /proc/cmdline:1: kernel.domainname = example com\n",
+            String::from_utf8_lossy(&output)
+        );
+
+        let shadowed_file = root.join("lib").join("10-a.conf");
+        fs::create_dir_all(root.join("lib"))?;
+        fs::write(&shadowed_file, "vm.swappiness = 99\n")?;
+        let origins = Origin::find(
+            &[],
+            &files,
+            &[(shadowed_file.clone(), Override::Shadowed(files[0].clone()))],
+            "vm.swappiness",
+        );
+        assert_eq!(4, origins.len());
+        assert!(origins[1].effective);
+        assert_eq!(
+            Some(Override::Shadowed(files[0].clone())),
+            origins[3].overridden
+        );
+        let mut output = Vec::new();
+        origins[3].display(&Config::default(), &mut output)?;
+        assert_eq!(
+            format!(
+                "  {}:1: vm.swappiness = 99 (shadowed by {})\n",
+                shadowed_file.display(),
+                files[0].display()
+            ),
+            String::from_utf8_lossy(&output)
+        );
+        Ok(())
+    }
+}
@@ -1,13 +1,155 @@
 use crate::config::Config;
 use crate::error::Result;
+use crate::sysctl::access::Access;
+use crate::sysctl::backend::Backend;
+use crate::sysctl::journal::{ChangeSource, Journal};
+use crate::sysctl::namespace::{self, Namespace};
 use crate::sysctl::r#type::DisplayType;
 use crate::sysctl::section::Section;
+use crate::sysctl::validation;
 use owo_colors::{OwoColorize, Stream::Stdout};
-use serde::{Deserialize, Serialize};
-use std::fmt::Write as _;
+use serde::{Deserialize, Serialize, Serializer};
+use std::convert::Infallible;
+use std::fmt::{self, Display, Formatter, Write as _};
 use std::io::Write;
 use std::path::PathBuf;
-use sysctl::{Ctl, Sysctl as SysctlImpl};
+use std::result::Result as StdResult;
+use std::str::FromStr;
+use sysctl::{Ctl, CtlFlags, Sysctl as SysctlImpl};
+
+/// Value of a kernel parameter.
+///
+/// There is no boolean variant since the kernel stores the boolean parameters
+/// (e.g. `net.ipv4.ip_forward`) as integers and only accepts `0` and `1` for them.
+/// See [`ParameterValue::as_bool`] for reading them as booleans.
+#[derive(Clone, Debug)]
+pub enum ParameterValue {
+    /// Integer value (e.g. `vm.swappiness`).
+    Integer(i128),
+    /// Whitespace separated integers (e.g. `net.ipv4.tcp_rmem`) along with the
+    /// original text for keeping the separators.
+    IntegerVector(Vec<i128>, String),
+    /// Single line of text (e.g. `kernel.ostype`).
+    String(String),
+    /// Multiple lines of text (e.g. `dev.cdrom.info`).
+    Text(String),
+}
+
+impl Default for ParameterValue {
+    fn default() -> Self {
+        Self::String(String::new())
+    }
+}
+
+impl FromStr for ParameterValue {
+    type Err = Infallible;
+    fn from_str(s: &str) -> StdResult<Self, Self::Err> {
+        if s.contains('\n') {
+            return Ok(Self::Text(s.to_string()));
+        }
+        let integers = s
+            .split_whitespace()
+            .map(|v| v.parse::<i128>())
+            .collect::<StdResult<Vec<i128>, _>>();
+        Ok(match integers {
+            Ok(integers) if integers.len() == 1 => Self::Integer(integers[0]),
+            Ok(integers) if integers.len() > 1 => Self::IntegerVector(integers, s.to_string()),
+            _ => Self::String(s.to_string()),
+        })
+    }
+}
+
+impl<'a> From<&'a str> for ParameterValue {
+    fn from(value: &'a str) -> Self {
+        match Self::from_str(value) {
+            Ok(value) => value,
+            Err(e) => match e {},
+        }
+    }
+}
+
+impl From<String> for ParameterValue {
+    fn from(value: String) -> Self {
+        Self::from(value.as_str())
+    }
+}
+
+impl<'a> From<&'a ParameterValue> for ParameterValue {
+    fn from(value: &'a ParameterValue) -> Self {
+        value.clone()
+    }
+}
+
+impl Display for ParameterValue {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Integer(value) => write!(f, "{value}"),
+            Self::IntegerVector(_, value) | Self::String(value) | Self::Text(value) => {
+                write!(f, "{value}")
+            }
+        }
+    }
+}
+
+impl Serialize for ParameterValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> StdResult<S::Ok, S::Error> {
+        match self {
+            Self::Integer(value) => value.serialize(serializer),
+            Self::IntegerVector(values, _) => values.serialize(serializer),
+            Self::String(value) | Self::Text(value) => value.serialize(serializer),
+        }
+    }
+}
+
+impl PartialEq for ParameterValue {
+    fn eq(&self, other: &Self) -> bool {
+        match (self.as_integers(), other.as_integers()) {
+            (Some(v1), Some(v2)) => v1 == v2,
+            (None, None) => self.to_string() == other.to_string(),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for ParameterValue {}
+
+impl ParameterValue {
+    /// Returns the name of the value type.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Self::Integer(_) => "integer",
+            Self::IntegerVector(..) => "integer_vector",
+            Self::String(_) => "string",
+            Self::Text(_) => "text",
+        }
+    }
+
+    /// Returns the value as a single integer if possible.
+    pub fn as_integer(&self) -> Option<i128> {
+        match self.as_integers()?.as_slice() {
+            [value] => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a list of integers if possible.
+    pub fn as_integers(&self) -> Option<Vec<i128>> {
+        match self {
+            Self::Integer(value) => Some(vec![*value]),
+            Self::IntegerVector(values, _) => Some(values.clone()),
+            Self::String(_) | Self::Text(_) => None,
+        }
+    }
+
+    /// Returns the value as a boolean if possible.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Integer(0) => Some(false),
+            Self::Integer(1) => Some(true),
+            _ => None,
+        }
+    }
+}
 
 /// Representation of a kernel parameter.
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -16,7 +158,7 @@ pub struct Parameter {
     pub name: String,
     /// Value of the kernel parameter.
     #[serde(skip)]
-    pub value: String,
+    pub value: ParameterValue,
     /// Description of the kernel parameter
     pub description: Option<String>,
     /// Section of the kernel parameter.
@@ -25,14 +167,19 @@ pub struct Parameter {
     pub docs_path: PathBuf,
     /// Title of the kernel parameter taken from the documentation.
     pub docs_title: String,
+    /// Access permissions of the kernel parameter.
+    #[serde(skip)]
+    pub access: Access,
 }
 
 impl<'a> TryFrom<&'a Ctl> for Parameter {
     type Error = crate::error::Error;
     fn try_from(ctl: &'a Ctl) -> Result<Self> {
+        let mut access = Access::from_path(ctl.path()).unwrap_or_default();
+        access.writable &= ctl.flags()?.contains(CtlFlags::WR);
         Ok(Parameter {
             name: ctl.name()?,
-            value: ctl.value_string()?,
+            value: ParameterValue::from(ctl.value_string()?),
             description: ctl
                 .description()
                 .ok()
@@ -40,6 +187,7 @@ impl<'a> TryFrom<&'a Ctl> for Parameter {
             section: Section::from_name(ctl.name()?),
             docs_path: PathBuf::new(),
             docs_title: String::new(),
+            access,
         })
     }
 }
@@ -107,6 +255,7 @@ impl Parameter {
                         component,
                         "=".if_supports_color(Stdout, |v| v.color(config.cli.color.default_color)),
                         self.value
+                            .to_string()
                             .replace('\n', " ")
                             .if_supports_color(Stdout, |v| v.bold())
                     );
@@ -136,13 +285,24 @@ impl Parameter {
                 )?;
             }
             DisplayType::Default => {
-                for value in self.value.lines() {
+                let shared =
+                    if config.target_namespace.is_some() && !self.get_namespace().is_namespaced() {
+                        format!(
+                            " {}",
+                            "(host)".if_supports_color(Stdout, |v| v
+                                .color(config.cli.color.default_color))
+                        )
+                    } else {
+                        String::new()
+                    };
+                for value in self.value.to_string().lines() {
                     writeln!(
                         output,
-                        "{} {} {}",
+                        "{} {} {}{}",
                         self.get_colored_name(config),
                         "=".if_supports_color(Stdout, |v| v.color(config.cli.color.default_color)),
                         value.if_supports_color(Stdout, |v| v.bold()),
+                        shared
                     )?;
                 }
             }
@@ -160,8 +320,12 @@ impl Parameter {
             .map(|p| {
                 serde_json::json!({
                     "name": p.name,
-                    "value": p.value,
+                    "value": p.value.to_string(),
+                    "typed_value": p.value,
                     "section": p.section.to_string(),
+                    "access": p.access,
+                    "namespace": p.get_namespace(),
+                    "kubelet_safe": p.is_kubelet_safe(),
                 })
             })
             .collect::<Vec<_>>();
@@ -193,23 +357,59 @@ impl Parameter {
         Ok(())
     }
 
-    /// Sets a new value for the kernel parameter.
+    /// Sets a new value for the kernel parameter using the given backend.
+    ///
+    /// The value is validated against the known rules before it is written.
+    /// Changes are recorded in the journal if it is configured.
     pub fn update_value<Output: Write>(
         &mut self,
         new_value: &str,
+        source: ChangeSource,
+        backend: &dyn Backend,
         config: &Config,
         output: &mut Output,
     ) -> Result<()> {
         log::trace!(target: "param", "Setting the value of {:?} to {:?}", self.name, new_value);
-        let ctl = Ctl::new(&self.name)?;
-        let new_value = ctl.set_value_string(new_value)?;
-        self.value = new_value;
+        validation::validate(
+            &self.name,
+            &ParameterValue::from(new_value),
+            &config.validation_rules,
+        )?;
+        if let Some(target) = &config.target_namespace {
+            if !self.get_namespace().is_namespaced() {
+                log::warn!(
+                    target: "namespace",
+                    "{} is shared with the host, the change is not limited to {}",
+                    self.name,
+                    target
+                );
+            }
+        }
+        let old_value = self.value.to_string();
+        self.value = ParameterValue::from(backend.write_value(&self.name, new_value)?);
+        Journal::record(
+            config,
+            &self.name,
+            &old_value,
+            &self.value.to_string(),
+            source,
+        );
         if !config.cli.quiet {
             self.display_value(config, output)?;
         }
         Ok(())
     }
 
+    /// Returns the kernel namespace that the parameter belongs to.
+    pub fn get_namespace(&self) -> Namespace {
+        Namespace::from_name(&self.name)
+    }
+
+    /// Returns `true` if the parameter is considered safe by the kubelet.
+    pub fn is_kubelet_safe(&self) -> bool {
+        namespace::is_kubelet_safe(&self.name)
+    }
+
     /// Performs a search for given query and returns true if
     /// the parameter is in the given sub/section.
     pub fn is_in_section(&self, query: &str) -> bool {
@@ -230,17 +430,59 @@ impl Parameter {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::sysctl::backend::KernelBackend;
     use owo_colors::colored::Color;
 
+    #[test]
+    fn test_parameter_value() {
+        assert_eq!(ParameterValue::Integer(-1), ParameterValue::from("-1"));
+        assert_eq!(
+            Some(vec![4096, 131072, 6291456]),
+            ParameterValue::from("4096\t131072\t6291456").as_integers()
+        );
+        assert_eq!(
+            ParameterValue::from("4096 131072 6291456"),
+            ParameterValue::from("4096\t131072\t6291456")
+        );
+        assert_eq!(
+            "4096 131072  6291456",
+            ParameterValue::from("4096 131072  6291456").to_string()
+        );
+        assert_eq!(
+            ParameterValue::String(String::from("on")),
+            ParameterValue::from("on")
+        );
+        assert_ne!(ParameterValue::from("1"), ParameterValue::from("yes"));
+        assert_eq!("Off", ParameterValue::from("Off").to_string());
+        assert_eq!(
+            ParameterValue::String(String::from("reno cubic")),
+            ParameterValue::from("reno cubic")
+        );
+        assert_eq!("text", ParameterValue::from("a\nb").type_name());
+        assert_ne!(ParameterValue::from("1"), ParameterValue::from("1 1"));
+        assert_ne!(ParameterValue::from("1"), ParameterValue::from("one"));
+        assert_eq!(
+            Some(18446744073692774399),
+            ParameterValue::from("18446744073692774399").as_integer()
+        );
+        assert_eq!(Some(false), ParameterValue::from("0").as_bool());
+        assert_eq!(None, ParameterValue::from("2").as_bool());
+        assert_eq!(
+            "[4,4,1,7]",
+            serde_json::to_string(&ParameterValue::from("4 4 1 7")).unwrap_or_default()
+        );
+    }
+
     #[test]
     fn test_sysctl_parameter() -> Result<()> {
         let mut parameter = Parameter {
             name: String::from("kernel.fictional.test_param"),
-            value: String::from("1"),
+            value: ParameterValue::from("1"),
             description: Some(String::from("This is a fictional parameter for testing")),
             section: Section::Kernel,
             docs_path: PathBuf::from("/etc/cosmos"),
             docs_title: String::from("Test Parameter"),
+            access: Access::default(),
         };
         assert_eq!(Some("test_param"), parameter.get_absolute_name());
 
@@ -315,7 +557,13 @@ mod tests {
         );
 
         assert!(parameter
-            .update_value("0", &config, &mut Vec::new())
+            .update_value(
+                "0",
+                ChangeSource::Cli,
+                &KernelBackend,
+                &config,
+                &mut Vec::new()
+            )
             .is_err());
 
         parameter.name = String::from("kernel.fictional.testing.xyz.parameter");
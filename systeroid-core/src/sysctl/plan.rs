@@ -0,0 +1,229 @@
+use crate::config::Config;
+use crate::error::Result;
+use crate::sysctl::controller::Sysctl;
+use crate::sysctl::parameter::ParameterValue;
+use crate::sysctl::validation;
+use crate::sysctl::DEPRECATED_PARAMS;
+use owo_colors::{OwoColorize, Stream::Stdout};
+use serde::Serialize;
+use std::fmt::{self, Display, Formatter};
+use std::io::Write;
+
+/// Status of a planned change.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanStatus {
+    /// Value would be changed.
+    Change,
+    /// Value is the same as the current value.
+    Unchanged,
+    /// Parameter does not exist.
+    Unknown,
+    /// Parameter is deprecated.
+    Deprecated,
+    /// Parameter is read-only.
+    ReadOnly,
+    /// Parameter is not writable by the current user.
+    PermissionDenied,
+    /// Value is rejected by the validation rules.
+    Invalid(String),
+}
+
+impl Display for PlanStatus {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Change => write!(f, "change"),
+            Self::Unchanged => write!(f, "unchanged"),
+            Self::Unknown => write!(f, "unknown parameter"),
+            Self::Deprecated => write!(f, "deprecated"),
+            Self::ReadOnly => write!(f, "read-only"),
+            Self::PermissionDenied => write!(f, "permission denied"),
+            Self::Invalid(reason) => write!(f, "{reason}"),
+        }
+    }
+}
+
+impl PlanStatus {
+    /// Returns `true` if the value cannot be set.
+    pub fn is_error(&self) -> bool {
+        !matches!(self, Self::Change | Self::Unchanged)
+    }
+}
+
+/// Change of a kernel parameter that would be made.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct PlannedChange {
+    /// Name of the kernel parameter.
+    pub name: String,
+    /// Current value of the parameter.
+    pub current_value: Option<String>,
+    /// Value to set.
+    pub new_value: String,
+    /// Status of the change.
+    pub status: PlanStatus,
+}
+
+impl PlannedChange {
+    /// Resolves what would be done for setting the value of the given parameter.
+    ///
+    /// Nothing is written to the backend.
+    pub fn new(sysctl: &Sysctl, name: &str, new_value: &str) -> Self {
+        let query = name.replace('/', ".");
+        let Some(parameter) = sysctl
+            .parameters
+            .iter()
+            .find(|parameter| parameter.name == query)
+            .or_else(|| {
+                let mut parameters = sysctl
+                    .parameters
+                    .iter()
+                    .filter(|parameter| parameter.get_absolute_name() == Some(&query));
+                parameters.next().filter(|_| parameters.next().is_none())
+            })
+        else {
+            return Self {
+                name: query,
+                current_value: None,
+                new_value: new_value.to_string(),
+                status: PlanStatus::Unknown,
+            };
+        };
+        let new_value = ParameterValue::from(new_value);
+        let status =
+            if DEPRECATED_PARAMS.contains(&parameter.get_absolute_name().unwrap_or_default()) {
+                PlanStatus::Deprecated
+            } else if parameter.access.is_read_only() {
+                PlanStatus::ReadOnly
+            } else if !parameter.access.writable {
+                PlanStatus::PermissionDenied
+            } else if let Some(reason) =
+                validation::get_rule(&parameter.name, &sysctl.config.validation_rules)
+                    .and_then(|rule| rule.check(&new_value))
+            {
+                PlanStatus::Invalid(reason)
+            } else if parameter.value == new_value {
+                PlanStatus::Unchanged
+            } else {
+                PlanStatus::Change
+            };
+        Self {
+            name: parameter.name.to_string(),
+            current_value: Some(parameter.value.to_string()),
+            new_value: new_value.to_string(),
+            status,
+        }
+    }
+
+    /// Prints the planned change to the given output.
+    pub fn display<Output: Write>(&self, config: &Config, output: &mut Output) -> Result<()> {
+        let format_value = |value: &str| {
+            value
+                .replace('\n', " ")
+                .if_supports_color(Stdout, |v| v.bold())
+                .to_string()
+        };
+        let values = match (&self.current_value, &self.status) {
+            (Some(current_value), PlanStatus::Unchanged) => format_value(current_value),
+            (Some(current_value), _) => format!(
+                "{} -> {}",
+                format_value(current_value),
+                format_value(&self.new_value)
+            ),
+            (None, _) => format_value(&self.new_value),
+        };
+        let separator = "=".if_supports_color(Stdout, |v| v.color(config.cli.color.default_color));
+        match &self.status {
+            PlanStatus::Change => writeln!(
+                output,
+                "{} {} {} {}",
+                "~".if_supports_color(Stdout, |v| v.yellow()),
+                self.name,
+                separator,
+                values
+            )?,
+            PlanStatus::Unchanged => writeln!(
+                output,
+                "{} {} {} {}",
+                "=".if_supports_color(Stdout, |v| v.green()),
+                self.name,
+                separator,
+                values
+            )?,
+            status => writeln!(
+                output,
+                "{} {} {} {} ({})",
+                "!".if_supports_color(Stdout, |v| v.red()),
+                self.name,
+                separator,
+                values,
+                status
+            )?,
+        }
+        Ok(())
+    }
+
+    /// Prints the given planned changes in JSON format.
+    pub fn display_bulk_json<Output: Write>(changes: &[Self], output: &mut Output) -> Result<()> {
+        writeln!(output, "{}", serde_json::to_string(changes)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sysctl::backend::DirectoryBackend;
+    use std::fs::{self, Permissions};
+    use std::os::unix::fs::PermissionsExt;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_planned_change() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let root = temp_dir.path().to_path_buf();
+        fs::create_dir_all(root.join("vm"))?;
+        fs::create_dir_all(root.join("kernel"))?;
+        fs::write(root.join("vm").join("swappiness"), "60\n")?;
+        fs::write(root.join("kernel").join("ostype"), "Linux\n")?;
+        fs::set_permissions(
+            root.join("kernel").join("ostype"),
+            Permissions::from_mode(0o444),
+        )?;
+        let sysctl =
+            Sysctl::init_with_backend(Config::default(), Arc::new(DirectoryBackend::new(&root)))?;
+
+        for (name, value, status) in [
+            ("vm.swappiness", "10", PlanStatus::Change),
+            ("vm/swappiness", "60", PlanStatus::Unchanged),
+            ("swappiness", "60", PlanStatus::Unchanged),
+            ("vm.unknown", "1", PlanStatus::Unknown),
+            ("kernel.ostype", "BSD", PlanStatus::ReadOnly),
+            (
+                "vm.swappiness",
+                "300",
+                PlanStatus::Invalid(String::from("300 is greater than the maximum value (200)")),
+            ),
+        ] {
+            assert_eq!(status, PlannedChange::new(&sysctl, name, value).status);
+        }
+
+        let mut output = Vec::new();
+        PlannedChange::new(&sysctl, "vm.swappiness", "10").display(&sysctl.config, &mut output)?;
+        PlannedChange::new(&sysctl, "kernel.ostype", "BSD").display(&sysctl.config, &mut output)?;
+        assert_eq!(
+            "~ vm.swappiness = 60 -> 10\n! kernel.ostype = Linux -> BSD (read-only)\n",
+            String::from_utf8_lossy(&output)
+        );
+
+        let mut output = Vec::new();
+        PlannedChange::display_bulk_json(
+            &[PlannedChange::new(&sysctl, "vm.unknown", "1")],
+            &mut output,
+        )?;
+        assert_eq!(
+            "[{\"name\":\"vm.unknown\",\"current_value\":null,\"new_value\":\"1\",\"status\":\"unknown\"}]\n",
+            String::from_utf8_lossy(&output)
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,567 @@
+use crate::config::Config;
+use crate::error::Result;
+use crate::sysctl::controller::Sysctl;
+use crate::sysctl::parameter::ParameterValue;
+use owo_colors::{OwoColorize, Stream::Stdout};
+use parseit::globwalk;
+use parseit::reader;
+use parseit::regex::{self, Regex};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::io::Write;
+use std::os::unix::fs::FileTypeExt;
+use std::path::{Path, PathBuf};
+
+/// Assignment of a kernel parameter in a preload file.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Assignment {
+    /// Name of the kernel parameter.
+    ///
+    /// It can be a glob pattern (e.g. `net.ipv4.conf.*.rp_filter`).
+    pub name: String,
+    /// Value to assign.
+    ///
+    /// It is `None` for the exclusions (i.e. the name is prefixed with `!`, or with `-`
+    /// and there is no value).
+    pub value: Option<String>,
+    /// Path of the file.
+    pub path: PathBuf,
+    /// Line number in the file.
+    pub line: usize,
+    /// Whether if the failures should be ignored (i.e. the name is prefixed with `-`).
+    pub ignore_failure: bool,
+}
+
+impl Assignment {
+    /// Normalizes the given parameter name.
+    ///
+    /// If the first separator is a slash, slashes and dots are swapped.
+    pub fn normalize_name(name: &str) -> String {
+        if name.find(['.', '/']).map(|i| &name[i..=i]) == Some("/") {
+            name.chars()
+                .map(|c| match c {
+                    '/' => '.',
+                    '.' => '/',
+                    c => c,
+                })
+                .collect()
+        } else {
+            name.to_string()
+        }
+    }
+
+    /// Parses the assignments in the given contents.
+    pub fn parse(contents: &str, path: &Path) -> Vec<Self> {
+        contents
+            .lines()
+            .enumerate()
+            .filter_map(|(i, line)| {
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                    return None;
+                }
+                let (name, value) = match line.split_once('=') {
+                    Some((name, _)) if name.starts_with('!') => {
+                        log::warn!(
+                            target: "sysctl",
+                            "{}:{}: ignoring the exclusion with a value",
+                            path.display(),
+                            i + 1
+                        );
+                        return None;
+                    }
+                    Some((name, value)) => (name.trim(), Some(value.trim().to_string())),
+                    None if line.starts_with(['!', '-']) => (line, None),
+                    None => {
+                        log::warn!(
+                            target: "sysctl",
+                            "{}:{}: ignoring the line without a value",
+                            path.display(),
+                            i + 1
+                        );
+                        return None;
+                    }
+                };
+                Some(Self {
+                    name: Self::normalize_name(name.trim_start_matches(['-', '!'])),
+                    value,
+                    path: path.to_path_buf(),
+                    line: i + 1,
+                    ignore_failure: name.starts_with('-'),
+                })
+            })
+            .collect()
+    }
+
+    /// Parses the assignments in the given file.
+    pub fn parse_file(path: &Path) -> Result<Vec<Self>> {
+        Ok(Self::parse(&reader::read_to_string(path)?, path))
+    }
+
+    /// Returns `true` if the name is a glob pattern.
+    pub fn is_glob(&self) -> bool {
+        self.name.contains(['*', '?', '['])
+    }
+
+    /// Returns `true` if the assignment applies to the given parameter.
+    pub fn matches(&self, name: &str) -> bool {
+        if self.is_glob() {
+            self.get_glob_regex()
+                .map(|glob_regex| glob_regex.is_match(name))
+                .unwrap_or(false)
+        } else {
+            self.name == name
+        }
+    }
+
+    /// Returns the regex for matching the parameter names if the name is a glob pattern.
+    ///
+    /// Wildcards only match within a single component of the name.
+    fn get_glob_regex(&self) -> Option<Regex> {
+        let mut pattern = String::from("^");
+        for c in self.name.chars() {
+            match c {
+                '*' => pattern.push_str("[^.]*"),
+                '?' => pattern.push_str("[^.]"),
+                '[' | ']' => pattern.push(c),
+                c => pattern.push_str(&regex::escape(&c.to_string())),
+            }
+        }
+        pattern.push('$');
+        Regex::new(&pattern)
+            .map_err(|e| log::error!(target: "sysctl", "invalid glob {:?}: {}", self.name, e))
+            .ok()
+    }
+}
+
+/// Returns `true` if the given file is masked (i.e. it is a symlink to `/dev/null`).
+fn is_masked(path: &Path) -> bool {
+    fs::metadata(path)
+        .map(|metadata| metadata.file_type().is_char_device())
+        .unwrap_or(false)
+}
+
+/// Reason of a preload file not being applied.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Override {
+    /// File is overridden by the file with the same name in a preceding directory.
+    Shadowed(PathBuf),
+    /// File is masked by a symlink to `/dev/null` with the same name.
+    Masked(PathBuf),
+}
+
+impl Display for Override {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Shadowed(path) => write!(f, "shadowed by {}", path.display()),
+            Self::Masked(path) => write!(f, "masked by {}", path.display()),
+        }
+    }
+}
+
+/// Returns the ".conf" files in the given directories grouped by their names
+/// along with the paths that point to a file.
+///
+/// The files in each group are in the order of the directories.
+fn collect_files<P: AsRef<Path>>(
+    preload_paths: &[P],
+) -> (BTreeMap<OsString, Vec<PathBuf>>, Vec<PathBuf>) {
+    let mut directory_files = BTreeMap::<OsString, Vec<PathBuf>>::new();
+    let mut extra_files = Vec::new();
+    for preload_path in preload_paths.iter().map(AsRef::as_ref) {
+        if preload_path.is_file() {
+            extra_files.push(preload_path.to_path_buf());
+        } else if let Ok(glob_walker) =
+            globwalk::glob(preload_path.join("*.conf").to_string_lossy())
+        {
+            for path in glob_walker.filter_map(|v| v.ok()).map(|v| v.into_path()) {
+                if let Some(file_name) = path.file_name() {
+                    directory_files
+                        .entry(file_name.to_os_string())
+                        .or_default()
+                        .push(path);
+                }
+            }
+        }
+    }
+    (directory_files, extra_files)
+}
+
+/// Returns the preload files in the order that they are applied.
+///
+/// This follows the semantics of `systemd-sysctl`:
+///
+/// - Directories are searched for the files with ".conf" extension.
+/// - A file overrides the files with the same name in the following directories.
+/// - Files that are symlinks to `/dev/null` are masked.
+/// - Files are sorted by their names regardless of their directories.
+///
+/// Paths that point to a file (e.g. `/etc/sysctl.conf`) are applied at last
+/// unless they are already included via a symlink.
+pub fn get_preload_files<P: AsRef<Path>>(preload_paths: &[P]) -> Vec<PathBuf> {
+    let (directory_files, extra_files) = collect_files(preload_paths);
+    let mut files = directory_files
+        .into_values()
+        .filter_map(|paths| paths.into_iter().next())
+        .filter(|path| {
+            let masked = is_masked(path);
+            if masked {
+                log::debug!(target: "sysctl", "Skipping masked file: {:?}", path);
+            }
+            !masked
+        })
+        .collect::<Vec<PathBuf>>();
+    let canonical_paths = files
+        .iter()
+        .filter_map(|path| fs::canonicalize(path).ok())
+        .collect::<Vec<PathBuf>>();
+    for path in extra_files {
+        if !fs::canonicalize(&path)
+            .map(|path| canonical_paths.contains(&path))
+            .unwrap_or(false)
+        {
+            files.push(path);
+        }
+    }
+    files
+}
+
+/// Returns the files in the given directories that are not applied
+/// since they are shadowed or masked by another file.
+pub fn get_overridden_files<P: AsRef<Path>>(preload_paths: &[P]) -> Vec<(PathBuf, Override)> {
+    let (directory_files, _) = collect_files(preload_paths);
+    directory_files
+        .into_values()
+        .flat_map(|paths| {
+            let mut paths = paths.into_iter();
+            let overriding_path = paths.next().unwrap_or_default();
+            let masked = is_masked(&overriding_path);
+            paths.filter(|path| !is_masked(path)).map(move |path| {
+                let reason = if masked {
+                    Override::Masked(overriding_path.clone())
+                } else {
+                    Override::Shadowed(overriding_path.clone())
+                };
+                (path, reason)
+            })
+        })
+        .collect()
+}
+
+/// Merges the assignments in the given files in the order that they are applied.
+///
+/// A later assignment of a name overrides the earlier one and glob patterns are not expanded.
+pub fn merge<P: AsRef<Path>>(files: &[P]) -> Vec<Assignment> {
+    let mut options: Vec<Assignment> = Vec::new();
+    for file in files {
+        match Assignment::parse_file(file.as_ref()) {
+            Ok(file_assignments) => {
+                for assignment in file_assignments {
+                    options.retain(|option| option.name != assignment.name);
+                    options.push(assignment);
+                }
+            }
+            Err(e) => {
+                log::error!(target: "sysctl", "cannot read {:?}: {}", file.as_ref(), e);
+            }
+        }
+    }
+    options
+}
+
+/// Resolves the effective assignments from the given files in the order that they are applied.
+///
+/// This follows the semantics of `systemd-sysctl`:
+///
+/// - A later assignment of a parameter overrides the earlier one.
+/// - Glob patterns are expanded to the matching parameters that are not explicitly assigned.
+/// - Exclusions prevent the parameters from being assigned via glob patterns.
+pub fn resolve<P: AsRef<Path>>(files: &[P], sysctl: &Sysctl) -> Vec<Assignment> {
+    let options = merge(files);
+    let mut assignments: Vec<Assignment> = Vec::new();
+    for option in options.iter().filter(|option| option.value.is_some()) {
+        if let Some(glob_regex) = option.get_glob_regex().filter(|_| option.is_glob()) {
+            for parameter in sysctl
+                .parameters
+                .iter()
+                .filter(|parameter| glob_regex.is_match(&parameter.name))
+            {
+                if options.iter().any(|option| option.name == parameter.name) {
+                    log::debug!(
+                        target: "sysctl",
+                        "Not setting {} (explicit setting exists)",
+                        parameter.name
+                    );
+                    continue;
+                }
+                assignments.retain(|assignment| assignment.name != parameter.name);
+                assignments.push(Assignment {
+                    name: parameter.name.to_string(),
+                    ..option.clone()
+                });
+            }
+        } else {
+            assignments.push(option.clone());
+        }
+    }
+    assignments
+}
+
+/// Returns the assignments of the given parameter that override the given file.
+///
+/// Overriding assignments are the ones in the files that are applied after the given file.
+/// Returns `None` if the file is not one of the given preload files (i.e. it is not applied).
+pub fn get_overrides<P: AsRef<Path>>(
+    files: &[P],
+    path: &Path,
+    name: &str,
+) -> Option<Vec<Assignment>> {
+    let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let index = files.iter().position(|file| {
+        fs::canonicalize(file)
+            .map(|file| file == path)
+            .unwrap_or(false)
+    })?;
+    Some(
+        files[index + 1..]
+            .iter()
+            .filter_map(|file| Assignment::parse_file(file.as_ref()).ok())
+            .flatten()
+            .filter(|assignment| assignment.value.is_some() && assignment.matches(name))
+            .collect(),
+    )
+}
+
+/// Drift of a kernel parameter from its configured value.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Drift {
+    /// Name of the kernel parameter.
+    pub name: String,
+    /// Current value of the parameter.
+    pub value: String,
+    /// Configured value of the parameter.
+    pub expected: String,
+    /// Path of the file that configures the value.
+    pub path: PathBuf,
+    /// Line number in the file.
+    pub line: usize,
+}
+
+impl Drift {
+    /// Compares the given assignments with the current values and returns the drifts.
+    ///
+    /// Assignments of the unknown parameters are skipped.
+    pub fn check(sysctl: &Sysctl, assignments: &[Assignment]) -> Vec<Self> {
+        assignments
+            .iter()
+            .filter_map(|assignment| {
+                let expected = assignment.value.as_ref()?;
+                let Some(parameter) = sysctl
+                    .parameters
+                    .iter()
+                    .find(|parameter| parameter.name == assignment.name)
+                else {
+                    log::debug!(target: "sysctl", "Skipping unknown parameter: {}", assignment.name);
+                    return None;
+                };
+                (parameter.value != ParameterValue::from(expected.as_str())).then(|| {
+                    Self {
+                        name: assignment.name.to_string(),
+                        value: parameter.value.to_string(),
+                        expected: expected.to_string(),
+                        path: assignment.path.to_path_buf(),
+                        line: assignment.line,
+                    }
+                })
+            })
+            .collect()
+    }
+
+    /// Prints the drift to the given output.
+    pub fn display<Output: Write>(&self, config: &Config, output: &mut Output) -> Result<()> {
+        writeln!(
+            output,
+            "{} {} {} (expected: {}, {}:{})",
+            self.name,
+            "=".if_supports_color(Stdout, |v| v.color(config.cli.color.default_color)),
+            self.value
+                .replace('\n', " ")
+                .if_supports_color(Stdout, |v| v.bold()),
+            self.expected.if_supports_color(Stdout, |v| v.bold()),
+            self.path.display(),
+            self.line
+        )?;
+        Ok(())
+    }
+
+    /// Prints the given drifts in JSON format.
+    pub fn display_bulk_json<Output: Write>(drifts: &[Self], output: &mut Output) -> Result<()> {
+        writeln!(output, "{}", serde_json::to_string(drifts)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sysctl::backend::DirectoryBackend;
+    use std::fs;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_preload() -> Result<()> {
+        assert_eq!(
+            "net.ipv4.conf.eth0/1.rp_filter",
+            Assignment::normalize_name("net/ipv4/conf/eth0.1/rp_filter")
+        );
+        assert_eq!(
+            "net.ipv4.ip_forward",
+            Assignment::normalize_name("net.ipv4.ip_forward")
+        );
+
+        let temp_dir = tempfile::tempdir()?;
+        let root = temp_dir.path().to_path_buf();
+        let etc_path = root.join("etc").join("sysctl.d");
+        let lib_path = root.join("usr").join("lib").join("sysctl.d");
+        fs::create_dir_all(&etc_path)?;
+        fs::create_dir_all(&lib_path)?;
+        fs::write(
+            etc_path.join("10-a.conf"),
+            "# comment\nvm.swappiness = 10\n-net.ipv4.ip_forward=1\ninvalid\n\
+            !net.ipv4.conf.lo.rp_filter\n!vm.swappiness = 1\n",
+        )?;
+        std::os::unix::fs::symlink("/dev/null", etc_path.join("50-mask.conf"))?;
+        fs::write(etc_path.join("README"), "vm.swappiness = 30\n")?;
+        fs::write(lib_path.join("10-a.conf"), "vm.swappiness = 99\n")?;
+        fs::write(
+            lib_path.join("20-b.conf"),
+            "net.ipv4.conf.*.rp_filter = 2\nvm.swappiness = 20\n",
+        )?;
+        fs::write(lib_path.join("50-mask.conf"), "kernel.hostname = masked\n")?;
+        fs::write(root.join("sysctl.conf"), "kernel/hostname = localhost\n")?;
+
+        let files = get_preload_files(&[
+            etc_path.clone(),
+            root.join("unexisting.d"),
+            lib_path.clone(),
+            root.join("sysctl.conf"),
+        ]);
+        assert_eq!(
+            vec![
+                etc_path.join("10-a.conf"),
+                lib_path.join("20-b.conf"),
+                root.join("sysctl.conf")
+            ],
+            files
+        );
+        assert_eq!(
+            vec![
+                (
+                    lib_path.join("10-a.conf"),
+                    Override::Shadowed(etc_path.join("10-a.conf"))
+                ),
+                (
+                    lib_path.join("50-mask.conf"),
+                    Override::Masked(etc_path.join("50-mask.conf"))
+                ),
+            ],
+            get_overridden_files(&[etc_path.clone(), lib_path.clone()])
+        );
+
+        for (path, value) in [
+            ("vm/swappiness", "60"),
+            ("kernel/hostname", "localhost"),
+            ("net/ipv4/ip_forward", "0"),
+            ("net/ipv4/conf/all/rp_filter", "0"),
+            ("net/ipv4/conf/eth0/rp_filter", "0"),
+            ("net/ipv4/conf/lo/rp_filter", "0"),
+        ] {
+            let path = root.join("proc").join(path);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, format!("{value}\n"))?;
+        }
+        let sysctl = Sysctl::init_with_backend(
+            Config::default(),
+            Arc::new(DirectoryBackend::new(root.join("proc"))),
+        )?;
+
+        let assignments = resolve(&files, &sysctl);
+        assert_eq!(
+            vec![
+                "net.ipv4.ip_forward",
+                "net.ipv4.conf.all.rp_filter",
+                "net.ipv4.conf.eth0.rp_filter",
+                "vm.swappiness",
+                "kernel.hostname"
+            ],
+            assignments
+                .iter()
+                .map(|v| v.name.as_str())
+                .collect::<Vec<&str>>()
+        );
+        assert_eq!(
+            Assignment {
+                name: String::from("net.ipv4.ip_forward"),
+                value: Some(String::from("1")),
+                path: etc_path.join("10-a.conf"),
+                line: 3,
+                ignore_failure: true,
+            },
+            assignments[0]
+        );
+        assert_eq!(lib_path.join("20-b.conf"), assignments[2].path);
+
+        let drifts = Drift::check(&sysctl, &assignments);
+        assert_eq!(4, drifts.len());
+        assert_eq!(
+            Drift {
+                name: String::from("vm.swappiness"),
+                value: String::from("60"),
+                expected: String::from("20"),
+                path: lib_path.join("20-b.conf"),
+                line: 2,
+            },
+            drifts[3]
+        );
+        let mut output = Vec::new();
+        drifts[3].display(&sysctl.config, &mut output)?;
+        assert_eq!(
+            format!(
+                "vm.swappiness = 60 (expected: 20, {}:2)\n",
+                lib_path.join("20-b.conf").display()
+            ),
+            String::from_utf8_lossy(&output)
+        );
+
+        let overrides = get_overrides(&files, &etc_path.join("10-a.conf"), "vm.swappiness");
+        assert_eq!(
+            Some(vec![(lib_path.join("20-b.conf"), 2)]),
+            overrides.map(|v| v.into_iter().map(|v| (v.path, v.line)).collect())
+        );
+        assert_eq!(
+            Some(1),
+            get_overrides(
+                &files,
+                &etc_path.join("10-a.conf"),
+                "net.ipv4.conf.lo.rp_filter"
+            )
+            .map(|v| v.len())
+        );
+        assert!(get_overrides(&files, &lib_path.join("10-a.conf"), "vm.swappiness").is_none());
+
+        fs::write(root.join("exclude.conf"), "-vm.swappiness\n")?;
+        let assignments = resolve(
+            &[lib_path.join("20-b.conf"), root.join("exclude.conf")],
+            &sysctl,
+        );
+        assert!(assignments.iter().all(|v| v.name != "vm.swappiness"));
+        assert_eq!(3, assignments.len());
+        Ok(())
+    }
+}
@@ -0,0 +1,323 @@
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::sysctl::preload::{self, Assignment};
+use owo_colors::{OwoColorize, Stream::Stdout};
+use parseit::reader;
+use serde::Serialize;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Extension of the profile files.
+const PROFILE_EXTENSION: &str = "conf";
+
+/// System-wide directory of the profiles.
+const SYSTEM_PROFILE_DIR: &str = "/etc/systeroid/profiles";
+
+/// Tuning profile that is a set of values in the sysctl.conf format.
+///
+/// Metadata is read from the comments at the beginning of the file:
+///
+/// ```conf
+/// # description: Low latency networking
+/// # include: base
+/// net.core.busy_poll = 50
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Profile {
+    /// Name of the profile.
+    pub name: String,
+    /// Path of the profile file.
+    pub path: PathBuf,
+    /// Description of the profile.
+    pub description: Option<String>,
+    /// Names of the profiles that are included (i.e. inherited).
+    pub includes: Vec<String>,
+}
+
+/// Profile along with its effective assignments.
+#[derive(Serialize)]
+struct ResolvedProfile<'a> {
+    /// Profile.
+    #[serde(flatten)]
+    profile: &'a Profile,
+    /// Assignments that are applied.
+    assignments: Vec<Assignment>,
+}
+
+impl Profile {
+    /// Returns the directories that the profiles are searched in.
+    ///
+    /// The directory in the configuration is used if it is set.
+    pub fn get_dirs(config: &Config) -> Vec<PathBuf> {
+        match &config.profile_dir {
+            Some(profile_dir) => vec![profile_dir.to_path_buf()],
+            None => dirs_next::config_dir()
+                .map(|path| path.join(env!("CARGO_PKG_NAME")).join("profiles"))
+                .into_iter()
+                .chain([PathBuf::from(SYSTEM_PROFILE_DIR)])
+                .collect(),
+        }
+    }
+
+    /// Parses the profile in the given file.
+    pub fn parse_file(path: &Path) -> Result<Self> {
+        let contents = reader::read_to_string(path)?;
+        let mut profile = Self {
+            name: path
+                .file_stem()
+                .map(|v| v.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            path: path.to_path_buf(),
+            description: None,
+            includes: Vec::new(),
+        };
+        for line in contents.lines().map(str::trim) {
+            let Some(comment) = line.strip_prefix('#').or_else(|| line.strip_prefix(';')) else {
+                if line.is_empty() {
+                    continue;
+                }
+                break;
+            };
+            match comment.split_once(':').map(|(k, v)| (k.trim(), v.trim())) {
+                Some(("description", description)) => {
+                    profile.description = Some(description.to_string());
+                }
+                Some(("include", includes)) => profile.includes.extend(
+                    includes
+                        .split([',', ' '])
+                        .filter(|v| !v.is_empty())
+                        .map(String::from),
+                ),
+                _ => {}
+            }
+        }
+        Ok(profile)
+    }
+
+    /// Finds the profile with the given name.
+    ///
+    /// The name can also be a path to the profile file.
+    pub fn find(name: &str, config: &Config) -> Result<Self> {
+        Self::find_in(name, &Self::get_dirs(config))
+    }
+
+    /// Finds the profile with the given name in the given directories.
+    fn find_in(name: &str, dirs: &[PathBuf]) -> Result<Self> {
+        let path = Path::new(name);
+        if name.contains('/') && path.is_file() {
+            return Self::parse_file(path);
+        }
+        dirs.iter()
+            .map(|dir| dir.join(format!("{name}.{PROFILE_EXTENSION}")))
+            .find(|path| path.is_file())
+            .ok_or_else(|| Error::ProfileError(format!("{name}: profile not found")))
+            .and_then(|path| Self::parse_file(&path))
+    }
+
+    /// Returns the available profiles sorted by their names.
+    ///
+    /// A profile overrides the profiles with the same name in the following directories.
+    pub fn list(config: &Config) -> Vec<Self> {
+        let mut profiles = Vec::<Self>::new();
+        for dir in Self::get_dirs(config) {
+            let Ok(entries) = fs::read_dir(&dir) else {
+                continue;
+            };
+            for path in entries.filter_map(|entry| Some(entry.ok()?.path())) {
+                if path.extension().and_then(|v| v.to_str()) != Some(PROFILE_EXTENSION) {
+                    continue;
+                }
+                match Self::parse_file(&path) {
+                    Ok(profile) if !profiles.iter().any(|p| p.name == profile.name) => {
+                        profiles.push(profile)
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        log::error!(target: "profile", "cannot read {:?}: {}", path, e);
+                    }
+                }
+            }
+        }
+        profiles.sort_by(|a, b| a.name.cmp(&b.name));
+        profiles
+    }
+
+    /// Returns the files of the included profiles and the profile itself in the order
+    /// that they are applied.
+    ///
+    /// Included profiles are searched in the directory of the including profile first.
+    pub fn get_files(&self, config: &Config) -> Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+        self.collect_files(&Self::get_dirs(config), &mut Vec::new(), &mut files)?;
+        Ok(files)
+    }
+
+    /// Collects the profile files recursively while detecting the include cycles.
+    fn collect_files(
+        &self,
+        dirs: &[PathBuf],
+        stack: &mut Vec<String>,
+        files: &mut Vec<PathBuf>,
+    ) -> Result<()> {
+        if stack.contains(&self.name) {
+            return Err(Error::ProfileError(format!(
+                "include cycle: {} -> {}",
+                stack.join(" -> "),
+                self.name
+            )));
+        }
+        if files.contains(&self.path) {
+            return Ok(());
+        }
+        stack.push(self.name.to_string());
+        let mut include_dirs = dirs.to_vec();
+        if let Some(parent) = self.path.parent() {
+            include_dirs.insert(0, parent.to_path_buf());
+        }
+        for include in &self.includes {
+            Self::find_in(include, &include_dirs)?.collect_files(dirs, stack, files)?;
+        }
+        stack.pop();
+        files.push(self.path.to_path_buf());
+        Ok(())
+    }
+
+    /// Returns the assignments of the profile along with the included profiles.
+    ///
+    /// Assignments in a profile override the ones in the profiles that it includes.
+    pub fn get_assignments(&self, config: &Config) -> Result<Vec<Assignment>> {
+        Ok(preload::merge(&self.get_files(config)?))
+    }
+
+    /// Prints the profile in the sysctl.conf format to the given output.
+    pub fn display<Output: Write>(&self, config: &Config, output: &mut Output) -> Result<()> {
+        let comment = |line: String| {
+            line.if_supports_color(Stdout, |v| v.color(config.cli.color.default_color))
+                .to_string()
+        };
+        writeln!(output, "{}", comment(format!("# profile: {}", self.name)))?;
+        if let Some(description) = &self.description {
+            writeln!(
+                output,
+                "{}",
+                comment(format!("# description: {description}"))
+            )?;
+        }
+        if !self.includes.is_empty() {
+            writeln!(
+                output,
+                "{}",
+                comment(format!("# include: {}", self.includes.join(", ")))
+            )?;
+        }
+        let mut path = None;
+        for assignment in self.get_assignments(config)? {
+            if path.as_ref() != Some(&assignment.path) {
+                writeln!(
+                    output,
+                    "{}",
+                    comment(format!("# {}", assignment.path.display()))
+                )?;
+                path = Some(assignment.path.to_path_buf());
+            }
+            match &assignment.value {
+                Some(value) => writeln!(
+                    output,
+                    "{}{} = {}",
+                    if assignment.ignore_failure { "-" } else { "" },
+                    assignment.name,
+                    value
+                )?,
+                None => writeln!(output, "!{}", assignment.name)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Prints the profile along with its assignments in JSON format.
+    pub fn display_json<Output: Write>(&self, config: &Config, output: &mut Output) -> Result<()> {
+        let resolved_profile = ResolvedProfile {
+            profile: self,
+            assignments: self.get_assignments(config)?,
+        };
+        writeln!(output, "{}", serde_json::to_string(&resolved_profile)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let root = temp_dir.path().to_path_buf();
+        fs::create_dir_all(&root)?;
+        fs::write(
+            root.join("base.conf"),
+            "# description: Base values\nvm.swappiness = 60\nkernel.sysrq = 0\n",
+        )?;
+        fs::write(
+            root.join("desktop.conf"),
+            "# description: Desktop\n# include: base\n\nvm.swappiness = 10\n",
+        )?;
+        fs::write(
+            root.join("latency.conf"),
+            "# include: desktop, base\n-net.core.busy_poll = 50\n",
+        )?;
+        fs::write(root.join("a.conf"), "# include: b\n")?;
+        fs::write(root.join("b.conf"), "# include: a\n")?;
+        let config = Config {
+            profile_dir: Some(root.clone()),
+            ..Default::default()
+        };
+
+        let profile = Profile::find("desktop", &config)?;
+        assert_eq!(Some(String::from("Desktop")), profile.description);
+        assert_eq!(vec![String::from("base")], profile.includes);
+        assert_eq!(
+            vec![
+                root.join("base.conf"),
+                root.join("desktop.conf"),
+                root.join("latency.conf")
+            ],
+            Profile::find("latency", &config)?.get_files(&config)?
+        );
+        assert_eq!(
+            vec![
+                ("kernel.sysrq", "0"),
+                ("vm.swappiness", "10"),
+                ("net.core.busy_poll", "50")
+            ],
+            Profile::find("latency", &config)?
+                .get_assignments(&config)?
+                .iter()
+                .map(|v| (v.name.as_str(), v.value.as_deref().unwrap_or_default()))
+                .collect::<Vec<(&str, &str)>>()
+        );
+        assert!(Profile::find("a", &config)?.get_files(&config).is_err());
+        assert!(Profile::find("unknown", &config).is_err());
+        assert_eq!(
+            vec!["a", "b", "base", "desktop", "latency"],
+            Profile::list(&config)
+                .iter()
+                .map(|v| v.name.as_str())
+                .collect::<Vec<&str>>()
+        );
+
+        let mut output = Vec::new();
+        profile.display(&config, &mut output)?;
+        assert_eq!(
+            format!(
+                "# profile: desktop\n# description: Desktop\n# include: base\n\
+                # {}\nkernel.sysrq = 0\n# {}\nvm.swappiness = 10\n",
+                root.join("base.conf").display(),
+                root.join("desktop.conf").display()
+            ),
+            String::from_utf8_lossy(&output)
+        );
+        Ok(())
+    }
+}
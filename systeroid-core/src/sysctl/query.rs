@@ -0,0 +1,580 @@
+use crate::error::{Error, Result};
+use crate::sysctl::access::AccessFilter;
+use crate::sysctl::parameter::{Parameter, ParameterValue};
+use parseit::regex::Regex;
+use std::cmp::Ordering;
+use std::fmt::{self, Display, Formatter};
+use std::iter::Peekable;
+use std::str::{Chars, FromStr};
+
+/// Operators and their symbols, longest first.
+const OPERATORS: &[(&str, Operator)] = &[
+    (":~", Operator::Matches),
+    ("!=", Operator::NotEqual),
+    (">=", Operator::GreaterEqual),
+    ("<=", Operator::LessEqual),
+    (":", Operator::Contains),
+    ("=", Operator::Equal),
+    (">", Operator::Greater),
+    ("<", Operator::Less),
+];
+
+/// Field of a parameter to filter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Field {
+    /// Name of the parameter.
+    Name,
+    /// Section of the parameter.
+    Section,
+    /// Value of the parameter.
+    Value,
+    /// Documentation of the parameter.
+    Doc,
+    /// Namespace of the parameter.
+    Namespace,
+    /// Whether if the parameter is writable by the current user.
+    Writable,
+    /// Whether if the parameter cannot be written by any user.
+    ReadOnly,
+}
+
+impl FromStr for Field {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "name" => Ok(Self::Name),
+            "section" => Ok(Self::Section),
+            "value" => Ok(Self::Value),
+            "doc" => Ok(Self::Doc),
+            "namespace" => Ok(Self::Namespace),
+            "writable" => Ok(Self::Writable),
+            "readonly" => Ok(Self::ReadOnly),
+            _ => Err(Error::QueryError(format!("unknown field: {s:?}"))),
+        }
+    }
+}
+
+impl Display for Field {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", format!("{self:?}").to_lowercase())
+    }
+}
+
+/// Comparison operator of a filter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operator {
+    /// `:` (contains, or equals for the section and namespace)
+    Contains,
+    /// `:~` (matches the regex)
+    Matches,
+    /// `=`
+    Equal,
+    /// `!=`
+    NotEqual,
+    /// `>`
+    Greater,
+    /// `>=`
+    GreaterEqual,
+    /// `<`
+    Less,
+    /// `<=`
+    LessEqual,
+}
+
+impl Display for Operator {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let symbol = OPERATORS
+            .iter()
+            .find(|(_, operator)| operator == self)
+            .map(|(symbol, _)| *symbol)
+            .unwrap_or_default();
+        write!(f, "{symbol}")
+    }
+}
+
+impl Operator {
+    /// Returns `true` if the given ordering satisfies the numeric comparison.
+    fn compare(&self, ordering: Ordering) -> bool {
+        match self {
+            Self::Greater => ordering == Ordering::Greater,
+            Self::GreaterEqual => ordering != Ordering::Less,
+            Self::Less => ordering == Ordering::Less,
+            Self::LessEqual => ordering != Ordering::Greater,
+            _ => false,
+        }
+    }
+}
+
+/// Operand of a filter.
+#[derive(Clone, Debug)]
+enum Operand {
+    /// Text to compare.
+    Text(String),
+    /// Regex to match.
+    Regex(Regex),
+    /// Number to compare the integer values with.
+    Number(i128),
+    /// Boolean flag.
+    Flag(bool),
+}
+
+/// Condition on a field of a parameter (e.g. `value>1000`).
+#[derive(Clone, Debug)]
+pub struct Filter {
+    /// Field to check.
+    pub field: Field,
+    /// Comparison operator.
+    pub operator: Operator,
+    /// Operand of the comparison.
+    operand: Operand,
+}
+
+impl Filter {
+    /// Constructs a new instance after validating the operator and value for the field.
+    pub fn new(field: Field, operator: Operator, value: &str) -> Result<Self> {
+        let invalid =
+            |reason: &str| Error::QueryError(format!("{field}{operator}{value}: {reason}"));
+        let operand = match (field, operator) {
+            (Field::Writable | Field::ReadOnly, Operator::Contains | Operator::Equal) => {
+                match value.to_lowercase().as_str() {
+                    "true" | "yes" | "1" => Operand::Flag(true),
+                    "false" | "no" | "0" => Operand::Flag(false),
+                    _ => return Err(invalid("expected true or false")),
+                }
+            }
+            (Field::Writable | Field::ReadOnly, _) => {
+                return Err(invalid("unsupported operator"));
+            }
+            (_, Operator::Matches) => {
+                Operand::Regex(Regex::new(value).map_err(|e| invalid(&e.to_string()))?)
+            }
+            (Field::Value, Operator::Contains | Operator::Equal | Operator::NotEqual) => {
+                Operand::Text(value.to_string())
+            }
+            (Field::Value, _) => {
+                Operand::Number(value.parse().map_err(|_| invalid("expected an integer"))?)
+            }
+            (_, Operator::Contains | Operator::Equal | Operator::NotEqual) => {
+                Operand::Text(value.to_string())
+            }
+            _ => return Err(invalid("unsupported operator")),
+        };
+        Ok(Self {
+            field,
+            operator,
+            operand,
+        })
+    }
+
+    /// Returns `true` if the given parameter satisfies the condition.
+    ///
+    /// Text comparisons are case-insensitive and the integer comparisons match if any
+    /// of the values (e.g. of `net.ipv4.tcp_rmem`) satisfies the comparison.
+    pub fn matches(&self, parameter: &Parameter) -> bool {
+        let text = match self.field {
+            Field::Name => parameter.name.to_string(),
+            Field::Section => parameter.section.to_string(),
+            Field::Value => parameter.value.to_string(),
+            Field::Doc => parameter.get_documentation().unwrap_or_default(),
+            Field::Namespace => parameter.get_namespace().to_string(),
+            Field::Writable | Field::ReadOnly => {
+                let value = if self.field == Field::Writable {
+                    parameter.access.writable
+                } else {
+                    parameter.access.is_read_only()
+                };
+                return matches!(self.operand, Operand::Flag(flag) if flag == value);
+            }
+        };
+        match &self.operand {
+            Operand::Regex(regex) => regex.is_match(&text),
+            Operand::Number(number) => parameter.value.as_integers().is_some_and(|values| {
+                values
+                    .iter()
+                    .any(|value| self.operator.compare(value.cmp(number)))
+            }),
+            Operand::Text(value) => {
+                let is_equal = if self.field == Field::Value {
+                    parameter.value == ParameterValue::from(value.as_str())
+                } else {
+                    text.eq_ignore_ascii_case(value)
+                };
+                match self.operator {
+                    Operator::Contains
+                        if matches!(self.field, Field::Section | Field::Namespace) =>
+                    {
+                        is_equal
+                    }
+                    Operator::Contains => text.to_lowercase().contains(&value.to_lowercase()),
+                    Operator::NotEqual => !is_equal,
+                    _ => is_equal,
+                }
+            }
+            Operand::Flag(_) => false,
+        }
+    }
+}
+
+/// Token of a query.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    /// `(`
+    Open,
+    /// `)`
+    Close,
+    /// `AND`, `and` or `&&`
+    And,
+    /// `OR`, `or` or `||`
+    Or,
+    /// `NOT`, `not`, `!` or `-`
+    Not,
+    /// Term that is quoted if it starts with a quote.
+    Word(String, bool),
+}
+
+/// Splits the given query into tokens.
+fn tokenize(input: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::Open);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::Close);
+            }
+            '!' | '-' => {
+                chars.next();
+                tokens.push(Token::Not);
+            }
+            _ => {
+                let quoted = c == '"';
+                let word = read_word(&mut chars)?;
+                tokens.push(match word.as_str() {
+                    "AND" | "and" | "&&" if !quoted => Token::And,
+                    "OR" | "or" | "||" if !quoted => Token::Or,
+                    "NOT" | "not" if !quoted => Token::Not,
+                    _ => Token::Word(word, quoted),
+                });
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// Reads a word until a whitespace or an unbalanced `)` while unquoting the quoted parts.
+fn read_word(chars: &mut Peekable<Chars>) -> Result<String> {
+    let mut word = String::new();
+    let mut depth = 0;
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => break,
+            ')' if depth == 0 => break,
+            '"' => {
+                chars.next();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => word.extend(chars.next()),
+                        Some(c) => word.push(c),
+                        None => return Err(Error::QueryError(String::from("unclosed quote"))),
+                    }
+                }
+                continue;
+            }
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ => {}
+        }
+        word.push(c);
+        chars.next();
+    }
+    Ok(word)
+}
+
+/// Query for filtering the parameters.
+///
+/// A query consists of terms such as `section:net`, `name:~tcp_.*mem`, `value>1000`,
+/// `writable:true` and `doc:"congestion control"` which are combined with `AND`
+/// (or juxtaposition), `OR`, `NOT` (or `!`, `-`) and parentheses. A term without a
+/// field matches the parameter names.
+#[derive(Clone, Debug, Default)]
+pub enum Query {
+    /// Matches all parameters.
+    #[default]
+    All,
+    /// Single condition.
+    Filter(Filter),
+    /// Negation of a query.
+    Not(Box<Query>),
+    /// Both of the queries.
+    And(Box<Query>, Box<Query>),
+    /// Any of the queries.
+    Or(Box<Query>, Box<Query>),
+}
+
+impl FromStr for Query {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
+        let mut parser = Parser {
+            tokens: tokenize(s)?,
+            index: 0,
+        };
+        if parser.tokens.is_empty() {
+            return Ok(Self::All);
+        }
+        let query = parser.parse_or()?;
+        match parser.next() {
+            Some(token) => Err(parser.unexpected(Some(token))),
+            None => Ok(query),
+        }
+    }
+}
+
+impl From<Regex> for Query {
+    fn from(regex: Regex) -> Self {
+        Self::Filter(Filter {
+            field: Field::Name,
+            operator: Operator::Matches,
+            operand: Operand::Regex(regex),
+        })
+    }
+}
+
+impl From<AccessFilter> for Query {
+    fn from(access_filter: AccessFilter) -> Self {
+        Self::Filter(Filter {
+            field: match access_filter {
+                AccessFilter::Writable => Field::Writable,
+                AccessFilter::ReadOnly => Field::ReadOnly,
+            },
+            operator: Operator::Equal,
+            operand: Operand::Flag(true),
+        })
+    }
+}
+
+impl Query {
+    /// Returns a query that matches both of the queries.
+    pub fn and(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::All, query) | (query, Self::All) => query,
+            (query, other) => Self::And(Box::new(query), Box::new(other)),
+        }
+    }
+
+    /// Returns `true` if any of the filters in the query checks the given field.
+    pub fn uses(&self, field: Field) -> bool {
+        match self {
+            Self::All => false,
+            Self::Filter(filter) => filter.field == field,
+            Self::Not(query) => query.uses(field),
+            Self::And(query, other) | Self::Or(query, other) => {
+                query.uses(field) || other.uses(field)
+            }
+        }
+    }
+
+    /// Returns `true` if the given parameter matches the query.
+    pub fn matches(&self, parameter: &Parameter) -> bool {
+        match self {
+            Self::All => true,
+            Self::Filter(filter) => filter.matches(parameter),
+            Self::Not(query) => !query.matches(parameter),
+            Self::And(query, other) => query.matches(parameter) && other.matches(parameter),
+            Self::Or(query, other) => query.matches(parameter) || other.matches(parameter),
+        }
+    }
+}
+
+/// Recursive descent parser for the queries.
+struct Parser {
+    /// Tokens of the query.
+    tokens: Vec<Token>,
+    /// Index of the next token.
+    index: usize,
+}
+
+impl Parser {
+    /// Returns the next token without consuming it.
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.index)
+    }
+
+    /// Consumes the next token.
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.index).cloned();
+        self.index += 1;
+        token
+    }
+
+    /// Returns the error for an unexpected token.
+    fn unexpected(&self, token: Option<Token>) -> Error {
+        Error::QueryError(match token {
+            Some(Token::Open) => String::from("unexpected '('"),
+            Some(Token::Close) => String::from("unexpected ')'"),
+            Some(Token::And) => String::from("unexpected AND"),
+            Some(Token::Or) => String::from("unexpected OR"),
+            Some(Token::Not) => String::from("unexpected NOT"),
+            Some(Token::Word(word, _)) => format!("unexpected {word:?}"),
+            None => String::from("unexpected end of query"),
+        })
+    }
+
+    /// Parses the alternatives (`a OR b`).
+    fn parse_or(&mut self) -> Result<Query> {
+        let mut query = self.parse_and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.next();
+            query = Query::Or(Box::new(query), Box::new(self.parse_and()?));
+        }
+        Ok(query)
+    }
+
+    /// Parses the conjunctions (`a AND b` or `a b`).
+    fn parse_and(&mut self) -> Result<Query> {
+        let mut query = self.parse_not()?;
+        loop {
+            match self.peek() {
+                Some(Token::And) => {
+                    self.next();
+                }
+                Some(Token::Open | Token::Not | Token::Word(..)) => {}
+                _ => break,
+            }
+            query = Query::And(Box::new(query), Box::new(self.parse_not()?));
+        }
+        Ok(query)
+    }
+
+    /// Parses the negations (`NOT a`).
+    fn parse_not(&mut self) -> Result<Query> {
+        if self.peek() == Some(&Token::Not) {
+            self.next();
+            return Ok(Query::Not(Box::new(self.parse_not()?)));
+        }
+        match self.next() {
+            Some(Token::Open) => {
+                let query = self.parse_or()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(query),
+                    _ => Err(Error::QueryError(String::from("missing ')'"))),
+                }
+            }
+            Some(Token::Word(word, quoted)) => Self::parse_term(&word, quoted),
+            token => Err(self.unexpected(token)),
+        }
+    }
+
+    /// Parses a term (e.g. `name:~tcp_.*mem` or `swappiness`).
+    fn parse_term(word: &str, quoted: bool) -> Result<Query> {
+        let field_end = word
+            .find(|c: char| !(c.is_ascii_alphabetic() || c == '_'))
+            .unwrap_or(word.len());
+        let (field, rest) = word.split_at(field_end);
+        let operator = OPERATORS
+            .iter()
+            .find(|(symbol, _)| rest.starts_with(symbol));
+        match operator {
+            Some((symbol, operator)) if !quoted && !field.is_empty() => Ok(Query::Filter(
+                Filter::new(field.parse()?, *operator, &rest[symbol.len()..])?,
+            )),
+            _ => Ok(Query::Filter(Filter::new(
+                Field::Name,
+                Operator::Contains,
+                word,
+            )?)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sysctl::access::Access;
+    use crate::sysctl::section::Section;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_query() -> Result<()> {
+        let parameter = |name: &str, value: &str, writable: bool| Parameter {
+            name: name.to_string(),
+            value: ParameterValue::from(value),
+            description: Some(String::from("TCP congestion control")),
+            section: Section::from_name(name.to_string()),
+            docs_path: PathBuf::new(),
+            docs_title: String::new(),
+            access: Access {
+                mode: Some(if writable { 0o644 } else { 0o444 }),
+                readable: true,
+                writable,
+            },
+        };
+        let parameters = [
+            parameter("net.ipv4.tcp_rmem", "4096\t131072\t6291456", true),
+            parameter("net.core.somaxconn", "4096", false),
+            parameter("vm.swappiness", "60", true),
+            parameter("kernel.hostname", "localhost", true),
+        ];
+        let find = |query: &str| -> Result<Vec<&str>> {
+            let query = Query::from_str(query)?;
+            Ok(parameters
+                .iter()
+                .filter(|parameter| query.matches(parameter))
+                .map(|parameter| parameter.name.as_str())
+                .collect())
+        };
+        assert_eq!(4, find("")?.len());
+        assert_eq!(vec!["vm.swappiness"], find("swap")?);
+        assert_eq!(
+            vec!["net.ipv4.tcp_rmem"],
+            find(r#"section:net name:~tcp_.*mem value>1000 writable:true doc:"congestion""#)?
+        );
+        assert_eq!(
+            vec!["net.core.somaxconn", "vm.swappiness"],
+            find("somaxconn OR (section:vm AND value<=60)")?
+        );
+        assert_eq!(
+            vec!["net.ipv4.tcp_rmem", "kernel.hostname"],
+            find("!readonly:true -swappiness")?
+        );
+        assert_eq!(vec!["net.core.somaxconn"], find("value=4096")?);
+        assert_eq!(vec!["net.ipv4.tcp_rmem"], find("value:131072")?);
+        assert_eq!(
+            vec!["kernel.hostname"],
+            find("NOT namespace:net value!=60")?
+        );
+        assert_eq!(vec!["net.ipv4.tcp_rmem"], find("name:~(rmem|wmem)$")?);
+        assert_eq!(Vec::<&str>::new(), find("\"section:net\"")?);
+
+        for query in [
+            "(section:net",
+            "section:net)",
+            "size>1",
+            "value>big",
+            "writable:maybe",
+            "name:~(",
+            "doc:\"congestion",
+            "swappiness OR",
+            "name>1",
+        ] {
+            assert!(Query::from_str(query).is_err(), "{query}");
+        }
+
+        let query = Query::from(AccessFilter::Writable)
+            .and(Query::from(Regex::new("^net").expect("invalid regex")));
+        assert!(query.matches(&parameters[0]));
+        assert!(!query.matches(&parameters[1]));
+
+        assert!(Query::from_str("swap OR NOT doc:congestion")?.uses(Field::Doc));
+        assert!(!Query::from_str("swap OR value>1")?.uses(Field::Doc));
+        assert!(!Query::All.uses(Field::Name));
+        Ok(())
+    }
+}
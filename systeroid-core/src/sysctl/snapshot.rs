@@ -0,0 +1,126 @@
+use crate::error::{Error, Result};
+use crate::sysctl::controller::Sysctl;
+use parseit::reader;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::time::SystemTime;
+
+/// Version of the snapshot format.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Snapshot of the kernel parameters.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Version of the snapshot format.
+    pub version: u32,
+    /// Kernel release that the snapshot is taken from.
+    pub kernel_release: String,
+    /// Hostname of the system.
+    pub hostname: String,
+    /// Creation date of the snapshot as UNIX timestamp.
+    pub timestamp: u64,
+    /// Names and values of the kernel parameters.
+    pub parameters: BTreeMap<String, String>,
+}
+
+impl Snapshot {
+    /// Takes a snapshot of the given parameters.
+    pub fn new(sysctl: &Sysctl) -> Result<Self> {
+        let get_value = |name: &str| {
+            sysctl
+                .parameters
+                .iter()
+                .find(|parameter| parameter.name == name)
+                .map(|parameter| parameter.value.to_string())
+                .unwrap_or_default()
+        };
+        Ok(Self {
+            version: SNAPSHOT_VERSION,
+            kernel_release: get_value("kernel.osrelease"),
+            hostname: get_value("kernel.hostname"),
+            timestamp: SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)?
+                .as_secs(),
+            parameters: sysctl
+                .parameters
+                .iter()
+                .map(|parameter| (parameter.name.to_string(), parameter.value.to_string()))
+                .collect(),
+        })
+    }
+
+    /// Parses the snapshot from the given string.
+    pub fn parse(input: &str) -> Result<Self> {
+        let snapshot: Self = serde_json::from_str(input)?;
+        if snapshot.version == 0 || snapshot.version > SNAPSHOT_VERSION {
+            return Err(Error::SnapshotError(format!(
+                "unsupported snapshot version: {}",
+                snapshot.version
+            )));
+        }
+        Ok(snapshot)
+    }
+
+    /// Reads the snapshot from the given file.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::parse(&reader::read_to_string(path)?)
+    }
+
+    /// Writes the snapshot to the given output.
+    pub fn write<Output: Write>(&self, output: &mut Output) -> Result<()> {
+        writeln!(output, "{}", serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Saves the snapshot to the given file.
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        self.write(&mut File::create(path)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::sysctl::backend::DirectoryBackend;
+    use std::fs;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_snapshot() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let root = temp_dir.path().to_path_buf();
+        fs::create_dir_all(root.join("kernel"))?;
+        fs::write(root.join("kernel").join("hostname"), "localhost\n")?;
+        fs::write(root.join("kernel").join("osrelease"), "6.1.0\n")?;
+        fs::write(root.join("kernel").join("printk"), "4\t4\t1\t7\n")?;
+
+        let sysctl =
+            Sysctl::init_with_backend(Config::default(), Arc::new(DirectoryBackend::new(&root)))?;
+        let snapshot = Snapshot::new(&sysctl)?;
+        assert_eq!(SNAPSHOT_VERSION, snapshot.version);
+        assert_eq!("6.1.0", snapshot.kernel_release);
+        assert_eq!("localhost", snapshot.hostname);
+        assert_eq!(
+            Some(&String::from("4\t4\t1\t7")),
+            snapshot.parameters.get("kernel.printk")
+        );
+
+        let path = root.join("snapshot.json");
+        snapshot.save_to_file(&path)?;
+        assert_eq!(snapshot, Snapshot::from_file(&path)?);
+
+        assert_eq!(
+            "snapshot error: `unsupported snapshot version: 2`",
+            Snapshot::parse(
+                r#"{"version":2,"kernel_release":"","hostname":"","timestamp":0,"parameters":{}}"#
+            )
+            .expect_err("unsupported version")
+            .to_string()
+        );
+        Ok(())
+    }
+}
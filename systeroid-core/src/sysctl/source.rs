@@ -0,0 +1,153 @@
+use crate::error::Result;
+use crate::sysctl::controller::Sysctl;
+use crate::sysctl::snapshot::Snapshot;
+use parseit::reader;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use std::path::PathBuf;
+
+/// Names and values of the kernel parameters.
+pub type ParameterValues = BTreeMap<String, String>;
+
+/// Keyword for the running kernel.
+pub const LIVE_SOURCE: &str = "live";
+
+/// Source of the kernel parameter values.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Source {
+    /// Running kernel (or the configured backend).
+    Live,
+    /// Snapshot, JSON output, sysctl.conf-style file or `sysctl -a` dump.
+    File(PathBuf),
+}
+
+impl<'a> From<&'a str> for Source {
+    fn from(s: &'a str) -> Self {
+        if s == LIVE_SOURCE {
+            Self::Live
+        } else {
+            Self::File(PathBuf::from(s))
+        }
+    }
+}
+
+impl Display for Source {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Live => write!(f, "{LIVE_SOURCE}"),
+            Self::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// Parameter entry in the JSON output.
+#[derive(Deserialize)]
+struct JsonParameter {
+    /// Name of the parameter.
+    name: String,
+    /// Value of the parameter.
+    value: String,
+}
+
+impl Source {
+    /// Reads the parameter values from the source.
+    pub fn read_values(&self, sysctl: &Sysctl) -> Result<ParameterValues> {
+        match self {
+            Self::Live => Ok(sysctl
+                .parameters
+                .iter()
+                .map(|parameter| (parameter.name.to_string(), parameter.value.to_string()))
+                .collect()),
+            Self::File(path) => parse_values(&reader::read_to_string(path)?),
+        }
+    }
+}
+
+/// Parses the parameter values from the given input.
+///
+/// The format is detected from the contents and it can be one of the following:
+///
+/// - snapshot (`--snapshot`)
+/// - JSON output (`--json`)
+/// - `name = value` lines (sysctl.conf or `sysctl -a` output)
+///
+/// Consecutive lines with the same name are joined as a multi-line value.
+pub fn parse_values(input: &str) -> Result<ParameterValues> {
+    let trimmed_input = input.trim_start();
+    if trimmed_input.starts_with('{') {
+        return Ok(Snapshot::parse(input)?.parameters);
+    } else if trimmed_input.starts_with('[') {
+        return Ok(serde_json::from_str::<Vec<JsonParameter>>(input)?
+            .into_iter()
+            .map(|parameter| (parameter.name, parameter.value))
+            .collect());
+    }
+    let mut values = ParameterValues::new();
+    let mut last_name = String::new();
+    for line in input.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        let Some((name, value)) = line.trim_start_matches('-').split_once('=') else {
+            log::trace!(target: "sysctl", "Skipping line: {:?}", line);
+            continue;
+        };
+        let name = name.trim().replace('/', ".");
+        let value = value.trim();
+        match values.get_mut(&name).filter(|_| name == last_name) {
+            Some(previous_value) => {
+                previous_value.push('\n');
+                previous_value.push_str(value);
+            }
+            None => {
+                values.insert(name.to_string(), value.to_string());
+            }
+        }
+        last_name = name;
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source() -> Result<()> {
+        assert_eq!(Source::Live, Source::from("live"));
+        assert_eq!(
+            "/etc/sysctl.conf",
+            Source::File(PathBuf::from("/etc/sysctl.conf")).to_string()
+        );
+
+        let values = parse_values(
+            "# comment\n; comment\nvm.swappiness = 10\n-net/ipv4/ip_forward=1\n\
+            dev.cdrom.info = a\ndev.cdrom.info = b\nvm.swappiness = 20\ninvalid\n",
+        )?;
+        assert_eq!(
+            vec![
+                ("dev.cdrom.info", "a\nb"),
+                ("net.ipv4.ip_forward", "1"),
+                ("vm.swappiness", "20"),
+            ],
+            values
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.as_str()))
+                .collect::<Vec<(&str, &str)>>()
+        );
+
+        let values =
+            parse_values(r#"[{"name":"kernel.hostname","value":"localhost","section":"kernel"}]"#)?;
+        assert_eq!(
+            Some(&String::from("localhost")),
+            values.get("kernel.hostname")
+        );
+
+        let values = parse_values(
+            r#"{"version":1,"kernel_release":"","hostname":"","timestamp":0,"parameters":{"vm.swappiness":"60"}}"#,
+        )?;
+        assert_eq!(Some(&String::from("60")), values.get("vm.swappiness"));
+        Ok(())
+    }
+}
@@ -0,0 +1,243 @@
+use crate::error::{Error, Result};
+use crate::sysctl::controller::Sysctl;
+use crate::sysctl::journal::{ChangeSource, Journal};
+use crate::sysctl::parameter::ParameterValue;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, DirBuilder, OpenOptions};
+use std::io::{Read, Write};
+use std::os::unix::fs::{DirBuilderExt, MetadataExt, OpenOptionsExt};
+use std::path::{Path, PathBuf};
+
+/// Directory of the pending transaction of the root user.
+const PENDING_DIR: &str = "/run/systeroid";
+
+/// Change of a kernel parameter value.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Change {
+    /// Name of the kernel parameter.
+    pub name: String,
+    /// Value before the change.
+    pub old_value: String,
+    /// Value after the change.
+    pub new_value: String,
+}
+
+/// Transaction that keeps track of the changes for rolling them back.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transaction {
+    /// Changes in the order that they are applied.
+    pub changes: Vec<Change>,
+}
+
+impl Transaction {
+    /// Records a change of the given parameter.
+    pub fn record(&mut self, name: &str, old_value: &ParameterValue, new_value: &ParameterValue) {
+        self.changes.push(Change {
+            name: name.to_string(),
+            old_value: old_value.to_string(),
+            new_value: new_value.to_string(),
+        });
+    }
+
+    /// Restores the old values of the changed parameters in the reverse order.
+    ///
+    /// Values are written without validation since they were read from the backend.
+    /// Restored values are recorded in the journal as undone changes.
+    /// Returns the result of the restore operation for each change.
+    pub fn rollback(self, sysctl: &mut Sysctl) -> Vec<(Change, Result<()>)> {
+        self.changes
+            .into_iter()
+            .rev()
+            .map(|change| {
+                log::trace!(target: "sysctl", "Rolling back {:?}", change);
+                let result = sysctl
+                    .backend
+                    .write_value(&change.name, &change.old_value)
+                    .map(|value| {
+                        Journal::record(
+                            &sysctl.config,
+                            &change.name,
+                            &change.new_value,
+                            &value,
+                            ChangeSource::Undo,
+                        );
+                        if let Some(parameter) = sysctl
+                            .parameters
+                            .iter_mut()
+                            .find(|parameter| parameter.name == change.name)
+                        {
+                            parameter.value = ParameterValue::from(value);
+                        }
+                    });
+                (change, result)
+            })
+            .collect()
+    }
+}
+
+/// Transaction that is waiting for a confirmation before it is rolled back.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingTransaction {
+    /// ID of the process that waits for the confirmation.
+    pub pid: u32,
+    /// Deadline of the confirmation as UNIX timestamp.
+    pub deadline: u64,
+    /// Transaction to roll back.
+    pub transaction: Transaction,
+}
+
+impl PendingTransaction {
+    /// Returns the path of the file that the pending transaction is stored.
+    ///
+    /// The path only depends on the effective user so that the changes can be confirmed
+    /// from another session regardless of its environment.
+    pub fn get_path() -> PathBuf {
+        // SAFETY: geteuid is always successful.
+        let uid = unsafe { libc::geteuid() };
+        let dir = if uid == 0 {
+            PathBuf::from(PENDING_DIR)
+        } else {
+            PathBuf::from("/run/user")
+                .join(uid.to_string())
+                .join(env!("CARGO_PKG_NAME"))
+        };
+        dir.join("pending.json")
+    }
+
+    /// Checks that the directory of the given file is only accessible by the current user.
+    ///
+    /// The directory is created with `0700` mode if it does not exist and `create` is set.
+    fn check_dir(path: &Path, create: bool) -> Result<()> {
+        let Some(dir) = path.parent() else {
+            return Ok(());
+        };
+        if create && !dir.exists() {
+            DirBuilder::new().recursive(true).mode(0o700).create(dir)?;
+        }
+        let metadata = fs::symlink_metadata(dir)?;
+        // SAFETY: geteuid is always successful.
+        let uid = unsafe { libc::geteuid() };
+        if !metadata.is_dir() || metadata.uid() != uid || metadata.mode() & 0o077 != 0 {
+            return Err(Error::TransactionError(format!(
+                "{} must be a directory that is only accessible by the current user",
+                dir.display()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if the process that waits for the confirmation is running.
+    pub fn is_active(&self) -> bool {
+        Path::new("/proc").join(self.pid.to_string()).exists()
+    }
+
+    /// Reads the pending transaction from the given file.
+    ///
+    /// Symbolic links are not followed.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::check_dir(path.as_ref(), false)?;
+        let mut contents = String::new();
+        OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_NOFOLLOW)
+            .open(path)?
+            .read_to_string(&mut contents)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    /// Saves the pending transaction to the given file.
+    ///
+    /// Symbolic links are not followed.
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        Self::check_dir(path.as_ref(), true)?;
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .custom_flags(libc::O_NOFOLLOW)
+            .open(path)?;
+        writeln!(file, "{}", serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::sysctl::backend::DirectoryBackend;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_transaction() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let root = temp_dir.path().to_path_buf();
+        fs::create_dir_all(root.join("vm"))?;
+        fs::write(root.join("vm").join("swappiness"), "60\n")?;
+
+        let mut sysctl =
+            Sysctl::init_with_backend(Config::default(), Arc::new(DirectoryBackend::new(&root)))?;
+        let mut transaction = Transaction::default();
+        for value in ["10", "20"] {
+            let parameter = sysctl
+                .parameters
+                .iter_mut()
+                .find(|parameter| parameter.name == "vm.swappiness")
+                .expect("parameter not found");
+            let old_value = parameter.value.clone();
+            parameter.update_value(
+                value,
+                ChangeSource::Cli,
+                sysctl.backend.as_ref(),
+                &sysctl.config,
+                &mut Vec::new(),
+            )?;
+            transaction.record(&parameter.name, &old_value, &parameter.value);
+        }
+        assert_eq!(
+            "20\n",
+            fs::read_to_string(root.join("vm").join("swappiness"))?
+        );
+
+        let results = transaction.rollback(&mut sysctl);
+        assert_eq!(
+            vec![("20", "10"), ("10", "60")],
+            results
+                .iter()
+                .map(|(change, _)| (change.new_value.as_str(), change.old_value.as_str()))
+                .collect::<Vec<(&str, &str)>>()
+        );
+        assert!(results.iter().all(|(_, result)| result.is_ok()));
+        assert_eq!(
+            "60\n",
+            fs::read_to_string(root.join("vm").join("swappiness"))?
+        );
+        assert_eq!(ParameterValue::Integer(60), sysctl.parameters[0].value);
+
+        let pending = PendingTransaction {
+            pid: std::process::id(),
+            deadline: 0,
+            transaction: Transaction {
+                changes: results.into_iter().map(|(change, _)| change).collect(),
+            },
+        };
+        assert!(pending.is_active());
+        let path = root.join("pending").join("pending.json");
+        pending.save_to_file(&path)?;
+        assert_eq!(pending, PendingTransaction::from_file(&path)?);
+        assert_eq!(0o700, fs::metadata(root.join("pending"))?.mode() & 0o777);
+
+        let link_path = root.join("pending").join("link.json");
+        std::os::unix::fs::symlink(root.join("target.json"), &link_path)?;
+        assert!(pending.save_to_file(&link_path).is_err());
+        assert!(!root.join("target.json").exists());
+
+        fs::set_permissions(root.join("pending"), fs::Permissions::from_mode(0o777))?;
+        assert!(pending.save_to_file(&path).is_err());
+        assert!(PendingTransaction::from_file(&path).is_err());
+        Ok(())
+    }
+}
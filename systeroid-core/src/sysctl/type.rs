@@ -48,6 +48,7 @@ macro_rules! gen_type_property {
 
 gen_type_property!(DisplayType, Name, Value, Binary,);
 gen_type_property!(OutputType, Tree, Json,);
+gen_type_property!(ReportType, Json, Junit, Sarif, Csv,);
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,320 @@
+use crate::error::{Error, Result};
+use crate::sysctl::parameter::ParameterValue;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+/// Bundled validation rules for the well-known parameters.
+///
+/// See [`Rule`] for the syntax.
+pub const DEFAULT_RULES: &[(&str, &str)] = &[
+    ("fs.file-max", "integer min=0"),
+    ("fs.protected_fifos", "integer values=0|1|2"),
+    ("fs.protected_hardlinks", "boolean"),
+    ("fs.protected_regular", "integer values=0|1|2"),
+    ("fs.protected_symlinks", "boolean"),
+    ("fs.suid_dumpable", "integer values=0|1|2"),
+    ("kernel.dmesg_restrict", "boolean"),
+    ("kernel.hostname", "string"),
+    ("kernel.kptr_restrict", "integer values=0|1|2"),
+    ("kernel.panic_on_oops", "boolean"),
+    ("kernel.pid_max", "integer min=301 max=4194304"),
+    ("kernel.printk", "integer[1..4] min=0"),
+    ("kernel.randomize_va_space", "integer values=0|1|2"),
+    ("kernel.sysrq", "integer min=0 max=511"),
+    ("kernel.unprivileged_bpf_disabled", "integer values=0|1|2"),
+    ("kernel.yama.ptrace_scope", "integer values=0|1|2|3"),
+    ("net.core.somaxconn", "integer min=0 max=2147483647"),
+    ("net.ipv4.conf.*.forwarding", "boolean"),
+    ("net.ipv4.conf.*.rp_filter", "integer values=0|1|2"),
+    ("net.ipv4.icmp_echo_ignore_all", "boolean"),
+    ("net.ipv4.ip_forward", "boolean"),
+    ("net.ipv4.ip_local_port_range", "integer[2] min=1 max=65535"),
+    ("net.ipv4.tcp_congestion_control", "string"),
+    ("net.ipv4.tcp_mem", "integer[3] min=0"),
+    ("net.ipv4.tcp_rmem", "integer[3] min=1"),
+    ("net.ipv4.tcp_syncookies", "integer values=0|1|2"),
+    ("net.ipv4.tcp_wmem", "integer[3] min=1"),
+    ("vm.dirty_background_ratio", "integer min=0 max=100"),
+    ("vm.dirty_ratio", "integer min=0 max=100"),
+    ("vm.overcommit_memory", "integer values=0|1|2"),
+    ("vm.overcommit_ratio", "integer min=0"),
+    ("vm.swappiness", "integer min=0 max=200"),
+    ("vm.vfs_cache_pressure", "integer min=0"),
+];
+
+lazy_static! {
+    /// Parsed version of the [`DEFAULT_RULES`].
+    static ref BUNDLED_RULES: Vec<(String, Rule)> = DEFAULT_RULES
+        .iter()
+        .map(|(name, rule)| {
+            (
+                name.to_string(),
+                Rule::from_str(rule).expect("failed to parse validation rule"),
+            )
+        })
+        .collect();
+}
+
+/// Expected type of a parameter value.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ValueType {
+    /// One or more integers.
+    Integer,
+    /// `0` or `1`.
+    Boolean,
+    /// Single line of text.
+    String,
+}
+
+/// Validation rule for a kernel parameter.
+///
+/// Rules are written as the value type followed by optional constraints:
+///
+/// ```text
+/// integer[3] min=1 max=2147483647
+/// integer[1..4] min=0
+/// integer values=0|1|2
+/// boolean
+/// string values=reno|cubic
+/// ```
+///
+/// The number (or range) in brackets is the expected count of values (arity) and
+/// `min`/`max` constraints are applied to each of them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rule {
+    /// Type of the value.
+    pub value_type: ValueType,
+    /// Expected number of values.
+    pub arity: RangeInclusive<usize>,
+    /// Minimum value.
+    pub min: Option<i128>,
+    /// Maximum value.
+    pub max: Option<i128>,
+    /// Allowed values.
+    pub values: Vec<ParameterValue>,
+}
+
+impl FromStr for Rule {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
+        let mut fields = s.split_whitespace();
+        let type_field = fields
+            .next()
+            .ok_or_else(|| Error::ValidationError(String::from("empty rule")))?;
+        let (type_name, arity) = match type_field.split_once('[') {
+            Some((type_name, arity)) => {
+                let invalid = || Error::ValidationError(format!("invalid arity: {arity:?}"));
+                let arity = arity.trim_end_matches(']');
+                let (min, max) = arity.split_once("..").unwrap_or((arity, arity));
+                let (min, max) = (
+                    min.parse().map_err(|_| invalid())?,
+                    max.parse().map_err(|_| invalid())?,
+                );
+                if min > max {
+                    return Err(invalid());
+                }
+                (type_name, min..=max)
+            }
+            None => (type_field, 1..=1),
+        };
+        let mut rule = Rule {
+            value_type: match type_name {
+                "integer" => ValueType::Integer,
+                "boolean" => ValueType::Boolean,
+                "string" => ValueType::String,
+                _ => {
+                    return Err(Error::ValidationError(format!(
+                        "unknown value type: {type_name:?}"
+                    )))
+                }
+            },
+            arity,
+            min: None,
+            max: None,
+            values: Vec::new(),
+        };
+        for field in fields {
+            let parse_bound = |value: &str| {
+                value
+                    .parse()
+                    .map_err(|_| Error::ValidationError(format!("invalid bound: {field:?}")))
+            };
+            match field.split_once('=') {
+                Some(("min", value)) => rule.min = Some(parse_bound(value)?),
+                Some(("max", value)) => rule.max = Some(parse_bound(value)?),
+                Some(("values", values)) => {
+                    rule.values = values.split('|').map(ParameterValue::from).collect()
+                }
+                _ => {
+                    return Err(Error::ValidationError(format!(
+                        "unknown constraint: {field:?}"
+                    )))
+                }
+            }
+        }
+        Ok(rule)
+    }
+}
+
+impl Rule {
+    /// Checks the given value against the rule.
+    ///
+    /// Returns a human-readable reason if the value is not valid.
+    pub fn check(&self, value: &ParameterValue) -> Option<String> {
+        match self.value_type {
+            ValueType::Integer => {
+                let Some(integers) = value.as_integers() else {
+                    return Some(format!(
+                        "expected an integer value, got {:?}",
+                        value.to_string()
+                    ));
+                };
+                if !self.arity.contains(&integers.len()) {
+                    let (min, max) = (self.arity.start(), self.arity.end());
+                    return Some(format!(
+                        "expected {}{} value{}, got {}",
+                        if min == max {
+                            String::new()
+                        } else {
+                            format!("{min} to ")
+                        },
+                        max,
+                        if *max == 1 { "" } else { "s" },
+                        integers.len()
+                    ));
+                }
+                for integer in integers {
+                    if let Some(min) = self.min.filter(|min| integer < *min) {
+                        return Some(format!("{integer} is less than the minimum value ({min})"));
+                    }
+                    if let Some(max) = self.max.filter(|max| integer > *max) {
+                        return Some(format!(
+                            "{integer} is greater than the maximum value ({max})"
+                        ));
+                    }
+                }
+            }
+            ValueType::Boolean => {
+                if value.as_bool().is_none() {
+                    return Some(format!(
+                        "expected a boolean value (0 or 1), got {:?}",
+                        value.to_string()
+                    ));
+                }
+            }
+            ValueType::String => {
+                if let ParameterValue::Text(_) = value {
+                    return Some(String::from("expected a single line of text"));
+                }
+            }
+        }
+        if !self.values.is_empty() && !self.values.contains(value) {
+            return Some(format!(
+                "expected one of {}, got {:?}",
+                self.values
+                    .iter()
+                    .map(|v| v.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", "),
+                value.to_string()
+            ));
+        }
+        None
+    }
+}
+
+/// Returns `true` if the given name matches the rule pattern.
+///
+/// `*` matches a single component of the name (e.g. `net.ipv4.conf.*.rp_filter`).
+pub(crate) fn matches_pattern(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.split('.').collect::<Vec<&str>>();
+    let name = name.split('.').collect::<Vec<&str>>();
+    pattern.len() == name.len()
+        && pattern
+            .iter()
+            .zip(name.iter())
+            .all(|(pattern, component)| *pattern == "*" || pattern == component)
+}
+
+/// Returns the rule for the given parameter.
+///
+/// The given rules take precedence over the bundled ones.
+pub fn get_rule<'a>(name: &str, rules: &'a [(String, Rule)]) -> Option<&'a Rule> {
+    rules
+        .iter()
+        .chain(BUNDLED_RULES.iter())
+        .find(|(pattern, _)| matches_pattern(pattern, name))
+        .map(|(_, rule)| rule)
+}
+
+/// Validates the new value of the given parameter.
+pub fn validate(name: &str, value: &ParameterValue, rules: &[(String, Rule)]) -> Result<()> {
+    match get_rule(name, rules).and_then(|rule| rule.check(value)) {
+        Some(reason) => Err(Error::ValidationError(format!("{name}: {reason}"))),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validation() -> Result<()> {
+        assert_eq!(
+            Rule {
+                value_type: ValueType::Integer,
+                arity: 3..=3,
+                min: Some(1),
+                max: None,
+                values: Vec::new(),
+            },
+            Rule::from_str("integer[3] min=1")?
+        );
+        assert_eq!(1..=4, Rule::from_str("integer[1..4]")?.arity);
+        assert!(Rule::from_str("integer[4..1]").is_err());
+        assert!(Rule::from_str("float").is_err());
+        assert!(Rule::from_str("integer max=x").is_err());
+        assert!(Rule::from_str("integer step=2").is_err());
+        assert_eq!(DEFAULT_RULES.len(), BUNDLED_RULES.len());
+
+        let rules = Vec::new();
+        assert!(validate("net.ipv4.tcp_rmem", &"4096 131072 6291456".into(), &rules).is_ok());
+        assert_eq!(
+            "validation error: `net.ipv4.tcp_rmem: expected 3 values, got 2`",
+            validate("net.ipv4.tcp_rmem", &"4096 131072".into(), &rules)
+                .expect_err("invalid arity")
+                .to_string()
+        );
+        assert!(validate("kernel.printk", &"3".into(), &rules).is_ok());
+        assert_eq!(
+            "validation error: `kernel.printk: expected 1 to 4 values, got 5`",
+            validate("kernel.printk", &"4 4 1 7 1".into(), &rules)
+                .expect_err("invalid arity")
+                .to_string()
+        );
+        assert!(validate("vm.swappiness", &"300".into(), &rules).is_err());
+        assert!(validate("vm.swappiness", &"abc".into(), &rules).is_err());
+        assert!(validate("net.ipv4.ip_forward", &"1".into(), &rules).is_ok());
+        assert!(validate("net.ipv4.ip_forward", &"on".into(), &rules).is_err());
+        assert!(validate("net.ipv4.ip_forward", &"2".into(), &rules).is_err());
+        assert!(validate("net.ipv4.conf.eth0.rp_filter", &"3".into(), &rules).is_err());
+        assert!(validate("kernel.hostname", &"a\nb".into(), &rules).is_err());
+        assert!(validate("unknown.param", &"anything".into(), &rules).is_ok());
+
+        let rules = vec![
+            (
+                String::from("vm.swappiness"),
+                Rule::from_str("integer max=100")?,
+            ),
+            (
+                String::from("net.ipv4.tcp_congestion_control"),
+                Rule::from_str("string values=reno|cubic")?,
+            ),
+        ];
+        assert!(validate("vm.swappiness", &"150".into(), &rules).is_err());
+        assert!(validate("net.ipv4.tcp_congestion_control", &"cubic".into(), &rules).is_ok());
+        assert!(validate("net.ipv4.tcp_congestion_control", &"bbr".into(), &rules).is_err());
+        Ok(())
+    }
+}
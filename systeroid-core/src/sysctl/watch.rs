@@ -0,0 +1,116 @@
+use crate::config::Config;
+use crate::error::Result;
+use crate::sysctl::transaction::Change;
+use chrono::{Local, TimeZone};
+use owo_colors::{OwoColorize, Stream::Stdout};
+use serde::Serialize;
+use std::io::Write;
+use std::time::SystemTime;
+
+/// Change of a kernel parameter value that is detected while watching.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct WatchEvent {
+    /// Date of the detection as UNIX timestamp.
+    pub timestamp: u64,
+    /// Detected change.
+    #[serde(flatten)]
+    pub change: Change,
+}
+
+impl WatchEvent {
+    /// Constructs a new instance for a change that is detected now.
+    pub fn new(change: Change) -> Result<Self> {
+        Ok(Self {
+            timestamp: SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)?
+                .as_secs(),
+            change,
+        })
+    }
+
+    /// Prints the event to the given output.
+    pub fn display<Output: Write>(&self, config: &Config, output: &mut Output) -> Result<()> {
+        let date = Local
+            .timestamp_opt(self.timestamp as i64, 0)
+            .single()
+            .map(|date| date.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(|| self.timestamp.to_string());
+        writeln!(
+            output,
+            "{} {} {} {} -> {}",
+            date.if_supports_color(Stdout, |v| v.color(config.cli.color.default_color)),
+            self.change.name,
+            "=".if_supports_color(Stdout, |v| v.color(config.cli.color.default_color)),
+            self.change.old_value.replace('\n', " "),
+            self.change
+                .new_value
+                .replace('\n', " ")
+                .if_supports_color(Stdout, |v| v.bold()),
+        )?;
+        Ok(())
+    }
+
+    /// Prints the event as a single line of JSON.
+    pub fn display_json<Output: Write>(&self, output: &mut Output) -> Result<()> {
+        writeln!(output, "{}", serde_json::to_string(self)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sysctl::backend::DirectoryBackend;
+    use crate::sysctl::controller::Sysctl;
+    use crate::sysctl::parameter::ParameterValue;
+    use std::fs;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_watch() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let root = temp_dir.path().to_path_buf();
+        fs::create_dir_all(root.join("vm"))?;
+        fs::write(root.join("vm").join("swappiness"), "60\n")?;
+        fs::write(root.join("vm").join("overcommit_memory"), "0\n")?;
+        let mut sysctl =
+            Sysctl::init_with_backend(Config::default(), Arc::new(DirectoryBackend::new(&root)))?;
+        assert!(sysctl.refresh(|_| true).is_empty());
+
+        fs::write(root.join("vm").join("swappiness"), "10\n")?;
+        fs::write(root.join("vm").join("overcommit_memory"), "1\n")?;
+        let changes = sysctl.refresh(|parameter| parameter.name == "vm.swappiness");
+        assert_eq!(
+            vec![Change {
+                name: String::from("vm.swappiness"),
+                old_value: String::from("60"),
+                new_value: String::from("10"),
+            }],
+            changes
+        );
+        assert_eq!(
+            Some(&ParameterValue::Integer(10)),
+            sysctl
+                .parameters
+                .iter()
+                .find(|parameter| parameter.name == "vm.swappiness")
+                .map(|parameter| &parameter.value)
+        );
+        assert_eq!(1, sysctl.refresh(|_| true).len());
+
+        let event = WatchEvent {
+            timestamp: 0,
+            change: changes[0].clone(),
+        };
+        let mut output = Vec::new();
+        event.display(&Config::default(), &mut output)?;
+        assert!(String::from_utf8_lossy(&output).ends_with(" vm.swappiness = 60 -> 10\n"));
+        let mut output = Vec::new();
+        event.display_json(&mut output)?;
+        assert_eq!(
+            "{\"timestamp\":0,\"name\":\"vm.swappiness\",\"old_value\":\"60\",\"new_value\":\"10\"}\n",
+            String::from_utf8_lossy(&output)
+        );
+        Ok(())
+    }
+}
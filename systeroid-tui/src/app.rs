@@ -1,15 +1,23 @@
 use crate::command::Command;
 use crate::error::Result;
 use crate::options::{CopyOption, Direction, ScrollArea};
-use crate::widgets::SelectableList;
+use crate::widgets::{SelectableList, ValueHistory};
 #[cfg(feature = "clipboard")]
 use copypasta_ext::{display::DisplayServer, ClipboardProviderExt};
 use log::{Level, LevelFilter};
 use std::str::FromStr;
 use std::time::Instant;
+use systeroid_core::error::Error as CoreError;
 use systeroid_core::sysctl::controller::Sysctl;
-use systeroid_core::sysctl::parameter::Parameter;
+use systeroid_core::sysctl::diff::ParameterDiff;
+use systeroid_core::sysctl::journal::ChangeSource;
+use systeroid_core::sysctl::namespace::NamespaceTarget;
+use systeroid_core::sysctl::parameter::{Parameter, ParameterValue};
+use systeroid_core::sysctl::preload::{self, Assignment};
+use systeroid_core::sysctl::profile::Profile;
+use systeroid_core::sysctl::query::{Field, Query};
 use systeroid_core::sysctl::section::Section;
+use systeroid_core::sysctl::source::{ParameterValues, Source};
 use tui_logger::TuiWidgetState;
 use unicode_width::UnicodeWidthStr;
 
@@ -132,6 +140,8 @@ pub struct App<'a> {
     pub input_cursor: u16,
     /// Whether if the search mode is enabled.
     pub search_mode: bool,
+    /// Error of the search query.
+    pub search_error: Option<String>,
     /// Y-scroll offset for the documentation.
     pub docs_scroll_amount: u16,
     /// Entries of the options menu.
@@ -142,6 +152,8 @@ pub struct App<'a> {
     pub section_list: SelectableList<String>,
     /// List of key bindings.
     pub key_bindings: SelectableList<&'a KeyBinding<'a>>,
+    /// History of the selected parameter value.
+    pub value_history: ValueHistory,
     #[cfg(feature = "clipboard")]
     /// Clipboard context.
     clipboard: Option<Box<dyn ClipboardProviderExt>>,
@@ -161,6 +173,7 @@ impl<'a> App<'a> {
             input_time: None,
             input_cursor: 0,
             search_mode: false,
+            search_error: None,
             docs_scroll_amount: 0,
             options: None,
             parameter_list: SelectableList::default(),
@@ -173,6 +186,7 @@ impl<'a> App<'a> {
                 sections
             }),
             key_bindings: SelectableList::default(),
+            value_history: ValueHistory::default(),
             #[cfg(feature = "clipboard")]
             clipboard: None,
             sysctl,
@@ -199,6 +213,11 @@ impl<'a> App<'a> {
         self.input.is_some() && self.input_time.is_none()
     }
 
+    /// Returns the namespace that the parameters are read from.
+    pub fn get_target_namespace(&self) -> Option<&NamespaceTarget> {
+        self.sysctl.config.target_namespace.as_ref()
+    }
+
     /// Sets the log message for the application.
     pub fn log(&mut self, level: Level, message: String) {
         log::log!(target: "tui", level, "{message}");
@@ -207,40 +226,46 @@ impl<'a> App<'a> {
     }
 
     /// Performs a search operation in the kernel parameter list.
+    ///
+    /// The input is parsed as a query and the list is kept as is if it is not valid
+    /// (e.g. while it is being typed).
     pub fn search(&mut self) {
         let section = self
             .section_list
             .selected()
             .map(|v| Section::from(v.to_string()))
             .unwrap_or(Section::Unknown);
-        if let Some(query) = &self.input {
-            self.parameter_list.items = self
-                .sysctl
-                .parameters
-                .clone()
-                .into_iter()
-                .filter(|param| {
-                    let mut found = param.name.contains(query);
-                    if section != Section::Unknown {
-                        found = found && section == param.section
-                    }
-                    found
-                })
-                .collect();
+        let query = match self.input.as_deref().map(Query::from_str) {
+            Some(Ok(query)) if query.uses(Field::Doc) && self.sysctl.config.tui.no_docs => {
+                self.search_error = Some(String::from("documentation is not loaded (--no-docs)"));
+                return;
+            }
+            Some(Ok(query)) => query,
+            Some(Err(e)) => {
+                self.search_error = Some(e.to_string());
+                return;
+            }
+            None => Query::All,
+        };
+        self.search_error = None;
+        let parameters = self
+            .sysctl
+            .parameters
+            .clone()
+            .into_iter()
+            .filter(|param| {
+                (section == Section::Unknown || param.section == section) && query.matches(param)
+            })
+            .collect();
+        if self.input.is_some() {
+            self.parameter_list.items = parameters;
             if self.parameter_list.items.is_empty() {
                 self.parameter_list.state.select(None);
             } else {
                 self.parameter_list.state.select(Some(0));
             }
         } else {
-            self.parameter_list = SelectableList::with_items(
-                self.sysctl
-                    .parameters
-                    .clone()
-                    .into_iter()
-                    .filter(|param| section == Section::Unknown || param.section == section)
-                    .collect(),
-            );
+            self.parameter_list = SelectableList::with_items(parameters);
         }
         self.docs_scroll_amount = 0;
     }
@@ -253,7 +278,7 @@ impl<'a> App<'a> {
             if let Some(parameter) = self.parameter_list.selected() {
                 match copy_option {
                     CopyOption::Name => clipboard.set_contents(parameter.name.clone()),
-                    CopyOption::Value => clipboard.set_contents(parameter.value.clone()),
+                    CopyOption::Value => clipboard.set_contents(parameter.value.to_string()),
                     CopyOption::Documentation => {
                         clipboard.set_contents(parameter.get_documentation().unwrap_or_default())
                     }
@@ -328,17 +353,27 @@ impl<'a> App<'a> {
                     self.input = Some(format!("save {} {}", parameter.name, parameter.value));
                 }
             }
-            Command::Set(param_name, new_value, save_to_file) => {
+            Command::Set(param_name, new_value, mut save_to_file) => {
                 if let Some(parameter) = self
                     .parameter_list
                     .items
                     .iter_mut()
                     .find(|param| param.name == param_name)
                 {
-                    match parameter.update_value(&new_value, &self.sysctl.config, &mut Vec::new()) {
+                    match parameter.update_value(
+                        new_value.as_str(),
+                        ChangeSource::Tui,
+                        self.sysctl.backend.as_ref(),
+                        &self.sysctl.config,
+                        &mut Vec::new(),
+                    ) {
                         Ok(()) => {
                             self.run_command(Command::Refresh)?;
                         }
+                        Err(e @ CoreError::ValidationError(_)) => {
+                            self.log(Level::Error, e.to_string());
+                            save_to_file = false;
+                        }
                         Err(e) => {
                             self.log(Level::Error, e.to_string());
                         }
@@ -423,10 +458,7 @@ impl<'a> App<'a> {
                 }
             }
             Command::Scroll(ScrollArea::Documentation, Direction::Up, amount) => {
-                self.docs_scroll_amount = self
-                    .docs_scroll_amount
-                    .checked_sub(amount.into())
-                    .unwrap_or_default();
+                self.docs_scroll_amount = self.docs_scroll_amount.saturating_sub(amount.into());
             }
             Command::Scroll(ScrollArea::Documentation, Direction::Down, amount) => {
                 self.docs_scroll_amount = self
@@ -454,6 +486,7 @@ impl<'a> App<'a> {
                 self.search_mode = true;
                 self.search();
                 self.input = Some(String::new());
+                self.search_error = None;
             }
             Command::ProcessInput => {
                 if self.input_time.is_some() {
@@ -546,16 +579,67 @@ impl<'a> App<'a> {
             Command::Refresh => {
                 self.input = None;
                 self.docs_scroll_amount = 0;
-                let parameters = Sysctl::init(self.sysctl.config.clone())?.parameters;
-                self.sysctl.parameters.iter_mut().for_each(|parameter| {
-                    if let Some(param) =
-                        parameters.iter().find(|param| param.name == parameter.name)
+                let changes = self.sysctl.refresh(|_| true);
+                for change in &changes {
+                    if let Some(parameter) = self
+                        .parameter_list
+                        .items
+                        .iter_mut()
+                        .find(|param| param.name == change.name)
                     {
-                        parameter.value = param.value.to_string();
+                        parameter.value = ParameterValue::from(change.new_value.as_str());
                     }
-                });
+                }
+                log::trace!(target: "tui", "Refreshed values: {:?}", changes);
                 self.log(Level::Info, String::from("Refreshed!"));
             }
+            Command::Diff(source) => {
+                let diffs = Source::from(source.as_str())
+                    .read_values(self.sysctl)
+                    .and_then(|values| {
+                        Ok(ParameterDiff::compute(
+                            &values,
+                            &Source::Live.read_values(self.sysctl)?,
+                        ))
+                    });
+                match diffs {
+                    Ok(diffs) => {
+                        self.parameter_list = SelectableList::with_items(
+                            self.sysctl
+                                .parameters
+                                .iter()
+                                .filter(|param| diffs.iter().any(|diff| diff.name == param.name))
+                                .cloned()
+                                .collect(),
+                        );
+                        self.docs_scroll_amount = 0;
+                        self.log(
+                            Level::Info,
+                            format!("{} parameters differ from {}", diffs.len(), source),
+                        );
+                    }
+                    Err(e) => {
+                        self.log(Level::Error, format!("Failed to compare: {e}"));
+                    }
+                }
+            }
+            Command::Profile(name, apply) => {
+                match Profile::find(&name, &self.sysctl.config)
+                    .and_then(|profile| profile.get_files(&self.sysctl.config))
+                {
+                    Ok(files) => {
+                        let assignments = preload::resolve(&files, self.sysctl);
+                        if apply {
+                            self.apply_profile(&name, assignments);
+                        } else {
+                            self.preview_profile(&name, assignments);
+                        }
+                    }
+                    Err(e) => {
+                        self.log(Level::Error, format!("Failed to load profile: {e}"));
+                    }
+                }
+            }
             Command::Cancel => {
                 if self.input.is_some() {
                     self.input = None;
@@ -576,7 +660,102 @@ impl<'a> App<'a> {
         Ok(())
     }
 
+    /// Shows the parameters that differ from the given profile assignments.
+    fn preview_profile(&mut self, name: &str, assignments: Vec<Assignment>) {
+        let new_values = assignments
+            .into_iter()
+            .filter_map(|assignment| Some((assignment.name, assignment.value?)))
+            .collect::<ParameterValues>();
+        let old_values = self
+            .sysctl
+            .parameters
+            .iter()
+            .filter(|parameter| new_values.contains_key(&parameter.name))
+            .map(|parameter| (parameter.name.to_string(), parameter.value.to_string()))
+            .collect::<ParameterValues>();
+        let diffs = ParameterDiff::compute(&old_values, &new_values);
+        for diff in &diffs {
+            log::info!(
+                target: "tui",
+                "{}: {} -> {}",
+                diff.name,
+                diff.old_value.as_deref().unwrap_or("(unknown)"),
+                diff.new_value.as_deref().unwrap_or_default()
+            );
+        }
+        self.parameter_list = SelectableList::with_items(
+            self.sysctl
+                .parameters
+                .iter()
+                .filter(|param| diffs.iter().any(|diff| diff.name == param.name))
+                .cloned()
+                .collect(),
+        );
+        self.docs_scroll_amount = 0;
+        self.log(
+            Level::Info,
+            format!("{} parameters differ from {name} profile", diffs.len()),
+        );
+    }
+
+    /// Sets the values of the given profile assignments.
+    fn apply_profile(&mut self, name: &str, assignments: Vec<Assignment>) {
+        let mut failed = 0;
+        let sysctl = &mut *self.sysctl;
+        for assignment in assignments {
+            let Some(value) = assignment.value else {
+                continue;
+            };
+            let Some(parameter) = sysctl
+                .parameters
+                .iter_mut()
+                .find(|parameter| parameter.name == assignment.name)
+            else {
+                if !assignment.ignore_failure {
+                    log::error!(target: "tui", "{}: unknown parameter", assignment.name);
+                    failed += 1;
+                }
+                continue;
+            };
+            if parameter.value == ParameterValue::from(value.as_str()) {
+                continue;
+            }
+            match parameter.update_value(
+                value.as_str(),
+                ChangeSource::File(assignment.path),
+                sysctl.backend.as_ref(),
+                &sysctl.config,
+                &mut Vec::new(),
+            ) {
+                Ok(()) => {
+                    if let Some(item) = self
+                        .parameter_list
+                        .items
+                        .iter_mut()
+                        .find(|item| item.name == parameter.name)
+                    {
+                        item.value = parameter.value.clone();
+                    }
+                }
+                Err(e) => {
+                    log::error!(target: "tui", "{}: {}", parameter.name, e);
+                    failed += 1;
+                }
+            }
+        }
+        if failed == 0 {
+            self.log(Level::Info, format!("Applied {name} profile"));
+        } else {
+            self.log(
+                Level::Error,
+                format!("Applied {name} profile ({failed} values could not be set)"),
+            );
+        }
+    }
+
     /// Handles the terminal tick event.
+    ///
+    /// Numeric values of the selected parameter are sampled for the history.
     pub fn tick(&mut self) {
         if let Some(instant) = self.input_time {
             if instant.elapsed().as_millis() > MESSAGE_DURATION {
@@ -584,5 +763,27 @@ impl<'a> App<'a> {
                 self.input_time = None;
             }
         }
+        let Some(parameter) = self
+            .parameter_list
+            .state
+            .selected()
+            .and_then(|index| self.parameter_list.items.get_mut(index))
+        else {
+            return;
+        };
+        if matches!(
+            parameter.value,
+            ParameterValue::Integer(_) | ParameterValue::IntegerVector(..)
+        ) {
+            match self.sysctl.backend.read_value(&parameter.name) {
+                Ok(value) => parameter.value = ParameterValue::from(value),
+                Err(e) => {
+                    log::trace!(target: "tui", "Cannot sample {:?}: {}", parameter.name, e);
+                    return;
+                }
+            }
+        }
+        self.value_history
+            .sample(&parameter.name, &parameter.value, Instant::now());
     }
 }
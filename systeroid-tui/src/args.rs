@@ -2,6 +2,7 @@ use getopts::Options;
 use std::env;
 use std::path::PathBuf;
 use systeroid_core::config::CONFIG_ENV;
+use systeroid_core::sysctl::namespace::NamespaceTarget;
 use systeroid_core::sysctl::section::Section;
 use systeroid_core::sysctl::KERNEL_DOCS_ENV;
 
@@ -24,6 +25,12 @@ pub struct Args {
     pub tick_rate: u64,
     /// Path of the Linux kernel documentation.
     pub kernel_docs: Option<PathBuf>,
+    /// Root directory of the kernel parameters.
+    pub root: Option<PathBuf>,
+    /// Path of the captured values to read the parameters from.
+    pub from: Option<PathBuf>,
+    /// Namespace to read and write the parameters from.
+    pub target_namespace: Option<NamespaceTarget>,
     /// Path for the changed parameters.
     pub save_path: Option<PathBuf>,
     /// File to save the logs.
@@ -58,6 +65,30 @@ impl Args {
             "set the path of the kernel documentation",
             "<path>",
         );
+        opts.optopt(
+            "",
+            "root",
+            "use a directory tree instead of /proc/sys",
+            "<path>",
+        );
+        opts.optopt(
+            "",
+            "from",
+            "read the parameters from a dump or sosreport (read-only)",
+            "<path>",
+        );
+        opts.optopt(
+            "",
+            "netns",
+            "use the parameters of a network namespace",
+            "<name>",
+        );
+        opts.optopt(
+            "",
+            "pid",
+            "use the parameters of the namespaces of a process",
+            "<pid>",
+        );
         opts.optopt(
             "",
             "save-path",
@@ -115,6 +146,10 @@ impl Args {
             println!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
             None
         } else {
+            let pid = matches
+                .opt_get::<u32>("pid")
+                .map_err(|e| eprintln!("error: `{e}`"))
+                .ok()?;
             Some(Args {
                 tick_rate: matches
                     .opt_get("t")
@@ -125,6 +160,12 @@ impl Args {
                     .opt_str("D")
                     .or_else(|| env::var(KERNEL_DOCS_ENV).ok())
                     .map(PathBuf::from),
+                root: matches.opt_str("root").map(PathBuf::from),
+                from: matches.opt_str("from").map(PathBuf::from),
+                target_namespace: matches
+                    .opt_str("netns")
+                    .map(NamespaceTarget::Netns)
+                    .or(pid.map(NamespaceTarget::Pid)),
                 save_path: matches.opt_str("save-path").map(PathBuf::from),
                 log_file: matches.opt_str("l"),
                 section: matches.opt_str("s").map(Section::from),
@@ -165,18 +206,33 @@ mod tests {
             String::from("1000"),
             String::from("-D"),
             String::from("/docs"),
+            String::from("--root"),
+            String::from("/mnt/proc/sys"),
             String::from("--no-docs"),
             String::from("-s"),
             String::from("vm"),
             String::from("-q"),
             String::from("test"),
+            String::from("--pid"),
+            String::from("1"),
+            String::from("--from"),
+            String::from("sosreport"),
         ])
         .expect("failed to parse arguments");
 
         assert_eq!(1000, args.tick_rate);
         assert_eq!(Some(PathBuf::from("/docs")), args.kernel_docs);
+        assert_eq!(Some(PathBuf::from("/mnt/proc/sys")), args.root);
         assert_eq!(Some(Section::Vm), args.section);
         assert_eq!(Some("test"), args.search_query.as_deref());
         assert!(args.no_docs);
+        assert_eq!(Some(NamespaceTarget::Pid(1)), args.target_namespace);
+        assert_eq!(Some(PathBuf::from("sosreport")), args.from);
+        assert!(Args::parse(vec![
+            String::new(),
+            String::from("--pid"),
+            String::from("x")
+        ])
+        .is_none());
     }
 }
@@ -59,6 +59,10 @@ pub enum Command {
     Copy,
     /// Refresh the application.
     Refresh,
+    /// Show the parameters that differ from the given source.
+    Diff(String),
+    /// Preview or apply the given profile.
+    Profile(String, bool),
     /// Cancel the operation.
     Cancel,
     /// Exit the application.
@@ -90,6 +94,17 @@ impl FromStr for Command {
                         values[1..].join(" "),
                         s.starts_with("save"),
                     ))
+                } else if let Some(("diff", values)) = s.split_once(char::is_whitespace) {
+                    Ok(Command::Diff(
+                        values.split_whitespace().next().ok_or(())?.to_string(),
+                    ))
+                } else if let Some((verb @ ("profile" | "apply"), values)) =
+                    s.split_once(char::is_whitespace)
+                {
+                    Ok(Command::Profile(
+                        values.split_whitespace().next().ok_or(())?.to_string(),
+                        verb == "apply",
+                    ))
                 } else if s.starts_with("scroll") {
                     let mut values = s.trim_start_matches("scroll").split_whitespace();
                     Ok(Command::Scroll(
@@ -181,6 +196,18 @@ mod tests {
                 Command::Set(String::from("a"), String::from("b c"), true),
                 "save a b c",
             ),
+            (
+                Command::Diff(String::from("snapshot.json")),
+                "diff snapshot.json",
+            ),
+            (
+                Command::Profile(String::from("desktop"), false),
+                "profile desktop",
+            ),
+            (
+                Command::Profile(String::from("desktop"), true),
+                "apply desktop",
+            ),
             (
                 Command::Scroll(ScrollArea::List, Direction::Up, 1),
                 "scroll list up 1",
@@ -201,6 +228,10 @@ mod tests {
             assert_eq!(Ok(command), Command::from_str(value));
         }
         assert!(Command::from_str("---").is_err());
+        assert!(Command::from_str("applyfoo desktop").is_err());
+        assert!(Command::from_str("profilex").is_err());
+        assert!(Command::from_str("diffx snapshot.json").is_err());
+        assert!(Command::from_str("apply").is_err());
         assert_command_parser! {
             input_mode: true,
             Key::Char('\n') => Command::ProcessInput,
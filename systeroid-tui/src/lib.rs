@@ -33,9 +33,12 @@ use ratatui::backend::Backend;
 use ratatui::Terminal;
 use std::env;
 use std::str::FromStr;
+use std::sync::Arc;
 use systeroid_core::cache::Cache;
 use systeroid_core::config::Config;
+use systeroid_core::sysctl::backend::{DirectoryBackend, OfflineBackend};
 use systeroid_core::sysctl::controller::Sysctl;
+use systeroid_core::sysctl::journal::Journal;
 use tui_logger::TuiLoggerFile;
 
 /// Runs `systeroid-tui`.
@@ -43,6 +46,7 @@ pub fn run<B: Backend>(args: Args, backend: B) -> Result<()> {
     let mut config = Config {
         display_deprecated: args.display_deprecated,
         kernel_docs: args.kernel_docs,
+        target_namespace: args.target_namespace,
         ..Default::default()
     };
     config.tui.tick_rate = args.tick_rate;
@@ -52,6 +56,12 @@ pub fn run<B: Backend>(args: Args, backend: B) -> Result<()> {
     config.tui.color.fg_color = args.fg_color;
     config.tui.color.bg_color = args.bg_color;
     config.parse(args.config)?;
+    if args.root.is_some() || args.from.is_some() || config.target_namespace.is_some() {
+        // journal entries are undone on the host, so the other targets are not recorded
+        config.journal_path = None;
+    } else if config.journal_path.is_none() {
+        config.journal_path = Journal::get_default_path();
+    }
     let colors = Colors::new(&config.tui.color.bg_color, &config.tui.color.fg_color)?;
     tui_logger::init_logger(if let Ok(log_level) = env::var("RUST_LOG") {
         LevelFilter::from_str(&log_level)?
@@ -64,7 +74,15 @@ pub fn run<B: Backend>(args: Args, backend: B) -> Result<()> {
         tui_logger::set_log_file(file_options);
     }
     log::trace!(target: "config", "{:?}", config);
-    let mut sysctl = Sysctl::init(config)?;
+    let mut sysctl = match (args.root, args.from) {
+        (_, Some(path)) => {
+            Sysctl::init_with_backend(config, Arc::new(OfflineBackend::load(path)?))?
+        }
+        (Some(root), None) => {
+            Sysctl::init_with_backend(config, Arc::new(DirectoryBackend::new(root)))?
+        }
+        (None, None) => Sysctl::init(config)?,
+    };
     if !sysctl.config.tui.no_docs {
         sysctl.update_docs_from_cache(&Cache::init()?)?;
     }
@@ -1,12 +1,12 @@
 use crate::app::{App, KeyBinding, HELP_TEXT};
 use crate::style::Colors;
-use crate::widgets::SelectableList;
+use crate::widgets::{SelectableList, ValueHistory};
 use ratatui::layout::{Alignment, Constraint, Direction, Layout, Margin, Position, Rect};
-use ratatui::style::{Color as TuiColor, Style};
+use ratatui::style::{Color as TuiColor, Modifier, Style};
 use ratatui::text::{Span, Text};
 use ratatui::widgets::{
     Block, BorderType, Borders, Cell, Clear, Paragraph, Row, Scrollbar, ScrollbarOrientation,
-    ScrollbarState, Table, Wrap,
+    ScrollbarState, Sparkline, Table, Wrap,
 };
 use ratatui::Frame;
 use tui_logger::{TuiLoggerLevelOutput, TuiLoggerSmartWidget};
@@ -18,6 +18,10 @@ pub fn render(frame: &mut Frame<'_>, app: &mut App, colors: &Colors) {
         .parameter_list
         .selected()
         .and_then(|parameter| parameter.get_documentation());
+    let show_history = app
+        .parameter_list
+        .selected()
+        .is_some_and(|parameter| app.value_history.is_available(&parameter.name));
     let rect = frame.area();
     let chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -30,7 +34,7 @@ pub fn render(frame: &mut Frame<'_>, app: &mut App, colors: &Colors) {
     {
         let chunks = Layout::default()
             .direction(Direction::Horizontal)
-            .constraints(if documentation.is_some() {
+            .constraints(if documentation.is_some() || show_history {
                 [Constraint::Percentage(50), Constraint::Percentage(50)]
             } else {
                 [Constraint::Percentage(100), Constraint::Min(0)]
@@ -50,14 +54,32 @@ pub fn render(frame: &mut Frame<'_>, app: &mut App, colors: &Colors) {
                 render_input_prompt(frame, chunks[1], chunks[0].height + 1, app, colors);
             }
         }
-        if let Some(documentation) = documentation {
-            render_parameter_documentation(
-                frame,
-                chunks[1],
-                documentation,
-                &mut app.docs_scroll_amount,
-                colors,
-            );
+        {
+            let history_height = if !show_history {
+                0
+            } else if documentation.is_some() {
+                u16::try_from(app.value_history.element_count() * 3 + 2)
+                    .unwrap_or(u16::MAX)
+                    .min(chunks[1].height / 2)
+            } else {
+                chunks[1].height
+            };
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length(history_height)])
+                .split(chunks[1]);
+            if let Some(documentation) = documentation {
+                render_parameter_documentation(
+                    frame,
+                    chunks[0],
+                    documentation,
+                    &mut app.docs_scroll_amount,
+                    colors,
+                );
+            }
+            if show_history {
+                render_value_history(frame, chunks[1], &app.value_history, colors);
+            }
         }
         render_selection_text(
             frame,
@@ -93,17 +115,26 @@ fn render_parameter_list(frame: &mut Frame<'_>, rect: Rect, app: &mut App, color
         .and_then(|v| u16::try_from(v).ok())
         .unwrap_or(1);
     let minimize_rows = rect.width < max_width + 10;
+    let target_namespace = app.get_target_namespace().cloned();
     let rows = app.parameter_list.items.iter().map(|item| {
-        let value = item.value.replace('\t', " ");
+        let mut value = item.value.to_string().replace('\t', " ");
+        if target_namespace.is_some() && !item.get_namespace().is_namespaced() {
+            value.push_str(" (host)");
+        }
+        let style = if item.access.is_read_only() {
+            colors.get_fg_style().add_modifier(Modifier::DIM)
+        } else {
+            colors.get_fg_style()
+        };
         Row::new(if minimize_rows {
             vec![Cell::from(Span::styled(
                 format!("{} = {}", item.name, value),
-                colors.get_fg_style(),
+                style,
             ))]
         } else {
             vec![
-                Cell::from(Span::styled(item.name.clone(), colors.get_fg_style())),
-                Cell::from(Span::styled(value, colors.get_fg_style())),
+                Cell::from(Span::styled(item.name.clone(), style)),
+                Cell::from(Span::styled(value, style)),
             ]
         })
         .height(1)
@@ -120,7 +151,13 @@ fn render_parameter_list(frame: &mut Frame<'_>, rect: Rect, app: &mut App, color
         )
         .block(
             Block::default()
-                .title(Span::styled("Parameters", colors.get_fg_style()))
+                .title(Span::styled(
+                    match &target_namespace {
+                        Some(target_namespace) => format!("Parameters ({target_namespace})"),
+                        None => String::from("Parameters"),
+                    },
+                    colors.get_fg_style(),
+                ))
                 .title_alignment(Alignment::Left)
                 .borders(Borders::all())
                 .border_style(colors.get_fg_style())
@@ -351,9 +388,9 @@ fn render_options_menu(
         .direction(Direction::Vertical)
         .constraints(
             [
-                Constraint::Length((rect.height.checked_sub(length_y)).unwrap_or_default() / 2),
+                Constraint::Length(rect.height.saturating_sub(length_y) / 2),
                 Constraint::Min(length_y),
-                Constraint::Length((rect.height.checked_sub(length_y)).unwrap_or_default() / 2),
+                Constraint::Length(rect.height.saturating_sub(length_y) / 2),
             ]
             .as_ref(),
         )
@@ -362,13 +399,9 @@ fn render_options_menu(
         .direction(Direction::Horizontal)
         .constraints(
             [
-                Constraint::Length(
-                    (popup_layout[1].width.checked_sub(length_x)).unwrap_or_default() / 2,
-                ),
+                Constraint::Length(popup_layout[1].width.saturating_sub(length_x) / 2),
                 Constraint::Min(length_x),
-                Constraint::Length(
-                    (popup_layout[1].width.checked_sub(length_x)).unwrap_or_default() / 2,
-                ),
+                Constraint::Length(popup_layout[1].width.saturating_sub(length_x) / 2),
             ]
             .as_ref(),
         )
@@ -401,6 +434,60 @@ fn render_options_menu(
     );
 }
 
+/// Renders the sparklines and statistics of the sampled values.
+fn render_value_history(
+    frame: &mut Frame<'_>,
+    rect: Rect,
+    value_history: &ValueHistory,
+    colors: &Colors,
+) {
+    let block = Block::default()
+        .title(Span::styled("History", colors.get_fg_style()))
+        .title_alignment(Alignment::Left)
+        .borders(Borders::all())
+        .border_style(colors.get_fg_style())
+        .border_type(BorderType::Rounded)
+        .style(colors.get_bg_style());
+    let area = block.inner(rect);
+    frame.render_widget(block, rect);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Length(3); value_history.element_count()])
+        .split(area);
+    for (index, chunk) in chunks.iter().enumerate() {
+        let Some(stats) = value_history.get_stats(index) else {
+            continue;
+        };
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(0)])
+            .split(*chunk);
+        frame.render_widget(
+            Paragraph::new(Span::styled(
+                format!(
+                    "{}min: {} max: {} rate: {:.2}/s",
+                    if value_history.element_count() > 1 {
+                        format!("[{index}] ")
+                    } else {
+                        String::new()
+                    },
+                    stats.min,
+                    stats.max,
+                    stats.rate
+                ),
+                colors.get_fg_style(),
+            )),
+            chunks[0],
+        );
+        frame.render_widget(
+            Sparkline::default()
+                .data(value_history.get_data(index, chunks[1].width.into()))
+                .style(colors.get_fg_style()),
+            chunks[1],
+        );
+    }
+}
+
 /// Renders the documentation of the selected sysctl parameter.
 fn render_parameter_documentation(
     frame: &mut Frame<'_>,
@@ -476,14 +563,25 @@ fn render_input_prompt(
         }
         None => String::new(),
     };
+    let mut block = Block::default()
+        .borders(Borders::all())
+        .border_style(colors.get_fg_style())
+        .border_type(BorderType::Rounded)
+        .style(colors.get_bg_style());
+    if let Some(error) = app
+        .search_error
+        .as_ref()
+        .filter(|_| app.search_mode && app.input_time.is_none())
+    {
+        block = block
+            .title(Span::styled(
+                format!(" {error} "),
+                Style::default().fg(TuiColor::Red),
+            ))
+            .title_alignment(Alignment::Right);
+    }
     frame.render_widget(
-        Paragraph::new(Span::styled(text, colors.get_fg_style())).block(
-            Block::default()
-                .borders(Borders::all())
-                .border_style(colors.get_fg_style())
-                .border_type(BorderType::Rounded)
-                .style(colors.get_bg_style()),
-        ),
+        Paragraph::new(Span::styled(text, colors.get_fg_style())).block(block),
         rect,
     );
 }
@@ -1,4 +1,10 @@
 use ratatui::widgets::TableState as State;
+use std::collections::VecDeque;
+use std::time::Instant;
+use systeroid_core::sysctl::parameter::ParameterValue;
+
+/// Maximum number of samples that are kept in the history.
+const HISTORY_CAPACITY: usize = 256;
 
 /// List widget with TUI controlled states.
 #[derive(Debug)]
@@ -63,9 +69,109 @@ impl<T> SelectableList<T> {
     }
 }
 
+/// Statistics of a sampled value.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ValueStats {
+    /// Minimum value.
+    pub min: i128,
+    /// Maximum value.
+    pub max: i128,
+    /// Average change per second.
+    pub rate: f64,
+}
+
+/// History of the numeric values of a parameter.
+#[derive(Debug, Default)]
+pub struct ValueHistory {
+    /// Name of the sampled parameter.
+    pub name: String,
+    /// Samples along with the time that they are taken.
+    samples: VecDeque<(Instant, Vec<i128>)>,
+}
+
+impl ValueHistory {
+    /// Adds a sample of the given parameter value.
+    ///
+    /// The history is reset if another parameter is sampled or the value is not numeric.
+    pub fn sample(&mut self, name: &str, value: &ParameterValue, instant: Instant) {
+        let values = match value {
+            ParameterValue::Integer(value) => vec![*value],
+            ParameterValue::IntegerVector(values, _) => values.clone(),
+            _ => Vec::new(),
+        };
+        if self.name != name
+            || values.is_empty()
+            || self
+                .samples
+                .back()
+                .is_some_and(|(_, last)| last.len() != values.len())
+        {
+            self.name = name.to_string();
+            self.samples.clear();
+        }
+        if values.is_empty() {
+            return;
+        }
+        if self.samples.len() == HISTORY_CAPACITY {
+            self.samples.pop_front();
+        }
+        self.samples.push_back((instant, values));
+    }
+
+    /// Returns `true` if there are enough samples of the given parameter to draw.
+    pub fn is_available(&self, name: &str) -> bool {
+        self.name == name && self.samples.len() > 1
+    }
+
+    /// Returns the number of the elements in each sample (e.g. 3 for `fs.file-nr`).
+    pub fn element_count(&self) -> usize {
+        self.samples
+            .back()
+            .map(|(_, values)| values.len())
+            .unwrap_or(0)
+    }
+
+    /// Returns the last samples of the given element relative to its minimum value.
+    pub fn get_data(&self, index: usize, count: usize) -> Vec<u64> {
+        let values = self
+            .samples
+            .iter()
+            .skip(self.samples.len().saturating_sub(count))
+            .filter_map(|(_, values)| values.get(index).copied())
+            .collect::<Vec<i128>>();
+        let min = values.iter().min().copied().unwrap_or_default();
+        values
+            .into_iter()
+            .map(|value| u64::try_from(value - min).unwrap_or(u64::MAX))
+            .collect()
+    }
+
+    /// Returns the statistics of the given element.
+    pub fn get_stats(&self, index: usize) -> Option<ValueStats> {
+        let samples = self
+            .samples
+            .iter()
+            .map(|(instant, values)| Some((*instant, *values.get(index)?)))
+            .collect::<Option<Vec<(Instant, i128)>>>()?;
+        let (first_instant, first_value) = samples.first()?;
+        let (last_instant, last_value) = samples.last()?;
+        let elapsed = last_instant.duration_since(*first_instant).as_secs_f64();
+        Some(ValueStats {
+            min: samples.iter().map(|(_, value)| *value).min()?,
+            max: samples.iter().map(|(_, value)| *value).max()?,
+            rate: if elapsed > 0.0 {
+                (last_value - first_value) as f64 / elapsed
+            } else {
+                0.0
+            },
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::time::Duration;
 
     #[test]
     fn test_selectable_list() {
@@ -84,4 +190,35 @@ mod tests {
         list.previous();
         assert_eq!(Some(0), list.state.selected());
     }
+
+    #[test]
+    fn test_value_history() {
+        let mut history = ValueHistory::default();
+        let instant = Instant::now();
+        for (seconds, value) in [(0, "100 0 50"), (1, "104 0 40"), (2, "110 0 45")] {
+            history.sample(
+                "fs.file-nr",
+                &ParameterValue::from(value),
+                instant + Duration::from_secs(seconds),
+            );
+        }
+        assert!(history.is_available("fs.file-nr"));
+        assert!(!history.is_available("fs.inode-nr"));
+        assert_eq!(3, history.element_count());
+        assert_eq!(vec![0, 4, 10], history.get_data(0, 10));
+        assert_eq!(vec![0, 5], history.get_data(2, 2));
+        assert_eq!(
+            Some(ValueStats {
+                min: 100,
+                max: 110,
+                rate: 5.0
+            }),
+            history.get_stats(0)
+        );
+        assert_eq!(None, history.get_stats(3));
+
+        history.sample("kernel.ostype", &ParameterValue::from("Linux"), instant);
+        assert!(!history.is_available("kernel.ostype"));
+        assert_eq!(0, history.element_count());
+    }
 }
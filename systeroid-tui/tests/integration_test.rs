@@ -2,11 +2,14 @@ use insta::assert_snapshot;
 use ratatui::backend::TestBackend;
 use ratatui::Terminal;
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::thread;
 use std::time::Duration;
 use systeroid_core::config::Config;
+use systeroid_core::sysctl::access::Access;
+use systeroid_core::sysctl::backend::KernelBackend;
 use systeroid_core::sysctl::controller::Sysctl;
-use systeroid_core::sysctl::parameter::Parameter;
+use systeroid_core::sysctl::parameter::{Parameter, ParameterValue};
 use systeroid_core::sysctl::section::Section;
 use systeroid_tui::app::App;
 use systeroid_tui::command::Command;
@@ -21,32 +24,36 @@ fn test_render_tui() -> Result<()> {
         parameters: vec![
             Parameter {
                 name: String::from("user.name"),
-                value: String::from("system"),
+                value: ParameterValue::from("system"),
                 description: None,
                 section: Section::User,
                 docs_path: PathBuf::new(),
                 docs_title: String::new(),
+                access: Access::default(),
             },
             Parameter {
                 name: String::from("kernel.fictional.test_param"),
-                value: String::from("0"),
+                value: ParameterValue::from("0"),
                 description: Some(String::from("This is a fictional parameter for testing")),
                 section: Section::Kernel,
                 docs_path: PathBuf::from("/etc/cosmos"),
                 docs_title: String::from("Test Parameter"),
+                access: Access::default(),
             },
             Parameter {
                 name: String::from("vm.stat_interval"),
-                value: String::from("1"),
+                value: ParameterValue::from("1"),
                 description: Some(String::from(
                     "The time interval between which vm statistics are updated",
                 )),
                 section: Section::Vm,
                 docs_path: PathBuf::from("/usr/share/doc/linux/admin-guide/sysctl/vm.rst"),
                 docs_title: String::from("stat_interval"),
+                access: Access::default(),
             },
         ],
         config: Config::default(),
+        backend: Arc::new(KernelBackend),
     };
     let mut app = App::new(&mut sysctl);
     let colors = Colors::default();
@@ -113,6 +120,16 @@ fn test_render_tui() -> Result<()> {
     app.run_command(Command::UpdateInput('_'))?;
     terminal.draw(|frame| render(frame, &mut app, &colors))?;
     assert_snapshot!("search", terminal.backend());
+    app.run_command(Command::UpdateInput(' '))?;
+    app.run_command(Command::UpdateInput('('))?;
+    assert_eq!(2, app.parameter_list.items.len());
+    assert_eq!(
+        Some("query error: `unexpected end of query`"),
+        app.search_error.as_deref()
+    );
+    app.run_command(Command::ClearInput(false))?;
+    app.run_command(Command::ClearInput(false))?;
+    assert!(app.search_error.is_none());
 
     app.run_command(Command::ProcessInput)?;
     app.run_command(Command::Scroll(ScrollArea::Documentation, Direction::Up, 1))?;
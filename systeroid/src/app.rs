@@ -1,17 +1,186 @@
-use parseit::globwalk;
 use parseit::reader;
 use parseit::regex::Regex;
 use std::env;
-use std::io::{self, BufRead, Write};
-use std::path::PathBuf;
-use std::process::{Command, Stdio};
-use systeroid_core::error::Result;
+use std::fs;
+use std::io::{self, BufRead, BufReader, IsTerminal, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, Stdio};
+use std::result::Result as StdResult;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+use systeroid_core::error::{Error, Result};
+use systeroid_core::sysctl::audit::{AuditReport, AuditRule, AuditStatus};
 use systeroid_core::sysctl::controller::Sysctl;
-use systeroid_core::sysctl::parameter::Parameter;
-use systeroid_core::sysctl::r#type::OutputType;
-use systeroid_core::sysctl::{DEPRECATED_PARAMS, SYSTEM_PRELOAD};
+use systeroid_core::sysctl::diff::ParameterDiff;
+use systeroid_core::sysctl::export::{self, ExportFormat};
+use systeroid_core::sysctl::fleet::{self, FleetReport};
+use systeroid_core::sysctl::journal::{ChangeSource, Journal, JournalEntry};
+use systeroid_core::sysctl::metrics;
+use systeroid_core::sysctl::namespace::NamespaceTarget;
+use systeroid_core::sysctl::origin::{self, Origin};
+use systeroid_core::sysctl::parameter::{Parameter, ParameterValue};
+use systeroid_core::sysctl::plan::PlannedChange;
+use systeroid_core::sysctl::preload::{self, Assignment, Drift};
+use systeroid_core::sysctl::profile::Profile;
+use systeroid_core::sysctl::query::{Field, Query};
+use systeroid_core::sysctl::r#type::{OutputType, ReportType};
+use systeroid_core::sysctl::snapshot::Snapshot;
+use systeroid_core::sysctl::source::{ParameterValues, Source};
+use systeroid_core::sysctl::transaction::{Change, PendingTransaction, Transaction};
+use systeroid_core::sysctl::watch::WatchEvent;
+use systeroid_core::sysctl::{DEPRECATED_PARAMS, KERNEL_CMDLINE_PATH, SYSTEM_PRELOAD};
 use systeroid_core::tree::{Tree, TreeNode};
 
+/// Default interval for checking the changes in the watch mode.
+pub const DEFAULT_WATCH_INTERVAL: Duration = Duration::from_millis(1000);
+
+/// Interval for checking the confirmation of the pending changes.
+const CONFIRMATION_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Timeout for reading the requests while serving the metrics.
+const METRICS_READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Timeout for writing the responses while serving the metrics.
+const METRICS_WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Maximum size of the requests (in bytes) while serving the metrics.
+const METRICS_MAX_REQUEST_SIZE: u64 = 8192;
+
+/// Maximum number of connections that are handled at the same time while serving the metrics.
+const METRICS_MAX_CONNECTIONS: usize = 16;
+
+/// Stream that fails to read or write once the deadline is reached.
+struct DeadlineStream<'a> {
+    /// Stream to read and write.
+    stream: &'a TcpStream,
+    /// Time that the reading or writing should be finished by.
+    deadline: Instant,
+}
+
+impl DeadlineStream<'_> {
+    /// Returns the remaining time until the deadline.
+    fn get_timeout(&self) -> io::Result<Duration> {
+        self.deadline
+            .checked_duration_since(Instant::now())
+            .filter(|timeout| !timeout.is_zero())
+            .ok_or_else(|| io::Error::from(io::ErrorKind::TimedOut))
+    }
+}
+
+impl Read for DeadlineStream<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.stream.set_read_timeout(Some(self.get_timeout()?))?;
+        let mut stream = self.stream;
+        stream.read(buf)
+    }
+}
+
+impl Write for DeadlineStream<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.stream.set_write_timeout(Some(self.get_timeout()?))?;
+        let mut stream = self.stream;
+        stream.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let mut stream = self.stream;
+        stream.flush()
+    }
+}
+
+/// Whether if the process is interrupted while waiting for a confirmation.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Handles the signals while waiting for a confirmation.
+///
+/// Hangups are ignored so that the changes are rolled back even if the session is lost.
+fn handle_signals() {
+    extern "C" fn interrupt(_: libc::c_int) {
+        INTERRUPTED.store(true, Ordering::SeqCst);
+    }
+    let interrupt_handler = interrupt as extern "C" fn(libc::c_int);
+    // SAFETY: the handler only stores an atomic value, which is async-signal-safe.
+    unsafe {
+        libc::signal(libc::SIGHUP, libc::SIG_IGN);
+        libc::signal(libc::SIGINT, interrupt_handler as libc::sighandler_t);
+        libc::signal(libc::SIGTERM, interrupt_handler as libc::sighandler_t);
+    }
+}
+
+/// Waits until the pending changes are confirmed or the timeout is reached.
+///
+/// The changes are confirmed if the file of the pending transaction is removed
+/// (i.e. via `--confirm`) or if "y" is entered when `interactive` is set.
+/// Returns `true` if the changes are confirmed.
+fn wait_for_confirmation(path: &Path, timeout: Duration, interactive: bool) -> bool {
+    let mut receiver = interactive.then(|| {
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            for line in io::stdin().lock().lines().map_while(StdResult::ok) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        receiver
+    });
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        if INTERRUPTED.load(Ordering::SeqCst) {
+            return false;
+        }
+        if !path.exists() {
+            return true;
+        }
+        match receiver
+            .as_ref()
+            .map(|receiver| receiver.recv_timeout(CONFIRMATION_POLL_INTERVAL))
+        {
+            Some(Ok(line)) => {
+                if matches!(line.trim().to_lowercase().as_str(), "y" | "yes") {
+                    return true;
+                }
+            }
+            Some(Err(RecvTimeoutError::Timeout)) => {}
+            Some(Err(RecvTimeoutError::Disconnected)) => receiver = None,
+            None => thread::sleep(CONFIRMATION_POLL_INTERVAL),
+        }
+    }
+    false
+}
+
+/// Mode for applying the values.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ApplyMode {
+    /// Apply the values one by one.
+    #[default]
+    Default,
+    /// Roll back all the changes if any of the values cannot be set.
+    Atomic,
+    /// Roll back the changes unless they are confirmed before the timeout.
+    RevertAfter(Duration),
+    /// Roll back the changes after the given duration.
+    Trial(Duration),
+    /// Show the changes without applying them.
+    DryRun,
+}
+
+/// Action to perform on a profile.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ProfileAction {
+    /// Show the values.
+    #[default]
+    Show,
+    /// Show the differences from the current values.
+    Diff,
+    /// Apply the values.
+    Apply,
+}
+
 /// Application controller.
 #[derive(Debug)]
 pub struct App<'a, Output: Write> {
@@ -19,12 +188,24 @@ pub struct App<'a, Output: Write> {
     sysctl: &'a mut Sysctl,
     /// Standard output.
     output: &'a mut Output,
+    /// Transaction for rolling back the changes.
+    transaction: Option<Transaction>,
+    /// Planned changes in the dry run mode.
+    plan: Option<Vec<PlannedChange>>,
+    /// Source of the changes for the journal.
+    source: ChangeSource,
 }
 
 impl<'a, Output: Write> App<'a, Output> {
     /// Constructs a new instance.
     pub fn new(sysctl: &'a mut Sysctl, output: &'a mut Output) -> Self {
-        Self { sysctl, output }
+        Self {
+            sysctl,
+            output,
+            transaction: None,
+            plan: None,
+            source: ChangeSource::Cli,
+        }
     }
 
     /// Prints the given parameters to stdout.
@@ -59,14 +240,11 @@ impl<'a, Output: Write> App<'a, Output> {
     }
 
     /// Displays all of the available kernel parameters.
-    pub fn display_parameters(&mut self, pattern: Option<Regex>, explain: bool) -> Result<()> {
+    pub fn display_parameters(&mut self, query: &Query, explain: bool) -> Result<()> {
         let parameters = self.sysctl.parameters.clone();
-        let mut parameters = parameters.iter().filter(|parameter| {
-            if let Some(pattern) = &pattern {
-                return pattern.is_match(&parameter.name);
-            }
-            true
-        });
+        let mut parameters = parameters
+            .iter()
+            .filter(|parameter| query.matches(parameter));
         if explain {
             parameters.try_for_each(|parameter| self.display_documentation(&parameter.name))
         } else {
@@ -74,6 +252,142 @@ impl<'a, Output: Write> App<'a, Output> {
         }
     }
 
+    /// Returns the differences between the given sources.
+    fn get_diffs(
+        &self,
+        old_source: Source,
+        new_source: Source,
+        pattern: Option<Regex>,
+    ) -> Result<Vec<ParameterDiff>> {
+        Ok(ParameterDiff::compute(
+            &old_source.read_values(self.sysctl)?,
+            &new_source.read_values(self.sysctl)?,
+        )
+        .into_iter()
+        .filter(|diff| {
+            if let Some(pattern) = &pattern {
+                return pattern.is_match(&diff.name);
+            }
+            true
+        })
+        .collect())
+    }
+
+    /// Displays the differences between the given sources.
+    pub fn display_diff(
+        &mut self,
+        old_source: Source,
+        new_source: Source,
+        pattern: Option<Regex>,
+    ) -> Result<()> {
+        let diffs = self.get_diffs(old_source, new_source, pattern)?;
+        self.print_diffs(&diffs)
+    }
+
+    /// Displays the differences between the namespaced parameters of the given
+    /// network namespaces.
+    pub fn display_netns_diff(
+        &mut self,
+        old_netns: &str,
+        new_netns: &str,
+        pattern: Option<Regex>,
+    ) -> Result<()> {
+        let diffs = ParameterDiff::compute(
+            &NamespaceTarget::Netns(old_netns.to_string()).read_values()?,
+            &NamespaceTarget::Netns(new_netns.to_string()).read_values()?,
+        )
+        .into_iter()
+        .filter(|diff| {
+            pattern
+                .as_ref()
+                .is_none_or(|pattern| pattern.is_match(&diff.name))
+        })
+        .collect::<Vec<ParameterDiff>>();
+        self.print_diffs(&diffs)
+    }
+
+    /// Exports the values in the new source that differ from the old source for a
+    /// container runtime.
+    pub fn export_diff(
+        &mut self,
+        old_source: Source,
+        new_source: Source,
+        pattern: Option<Regex>,
+        format: ExportFormat,
+    ) -> Result<()> {
+        let values = self
+            .get_diffs(old_source, new_source, pattern)?
+            .into_iter()
+            .filter_map(|diff| Some((diff.name, diff.new_value?)))
+            .collect::<Vec<(String, String)>>();
+        export::export(format, &values, self.output)
+    }
+
+    /// Exports the values of the given parameters (or sections) for a container runtime.
+    ///
+    /// A value can be given along with the name (i.e. `name=value`) to export it instead of
+    /// the current value. All parameters are exported if no names are given.
+    pub fn export_parameters(
+        &mut self,
+        format: ExportFormat,
+        names: Vec<String>,
+        query: &Query,
+    ) -> Result<()> {
+        let mut values = Vec::new();
+        if names.is_empty() {
+            values.extend(
+                self.sysctl
+                    .parameters
+                    .iter()
+                    .filter(|parameter| query.matches(parameter))
+                    .map(|parameter| (parameter.name.to_string(), parameter.value.to_string())),
+            );
+        }
+        for name in names {
+            match name.split_once('=') {
+                Some((name, value)) => {
+                    values.push((name.trim().replace('/', "."), value.trim().to_string()))
+                }
+                None => values.extend(
+                    self.sysctl
+                        .get_parameters(&name)
+                        .into_iter()
+                        .filter(|parameter| query.matches(parameter))
+                        .map(|parameter| (parameter.name.to_string(), parameter.value.to_string())),
+                ),
+            }
+        }
+        export::export(format, &values, self.output)
+    }
+
+    /// Prints the given differences to stdout.
+    fn print_diffs(&mut self, diffs: &[ParameterDiff]) -> Result<()> {
+        match self.sysctl.config.cli.output_type {
+            OutputType::Default => {
+                diffs
+                    .iter()
+                    .try_for_each(|diff| diff.display(&self.sysctl.config, self.output))?;
+            }
+            OutputType::Tree => {
+                let mut root_node = TreeNode::default();
+                diffs.iter().for_each(|diff| {
+                    root_node.add(
+                        &mut diff
+                            .get_tree_components(&self.sysctl.config)
+                            .iter()
+                            .map(|v| v.as_ref()),
+                    );
+                });
+                Tree::new(root_node.children)
+                    .print(self.output, self.sysctl.config.cli.color.default_color)?;
+            }
+            OutputType::Json => {
+                ParameterDiff::display_bulk_json(diffs, self.output)?;
+            }
+        }
+        Ok(())
+    }
+
     /// Displays the documentation of a parameter.
     pub fn display_documentation(&mut self, param_name: &str) -> Result<()> {
         let no_pager = self.sysctl.config.cli.no_pager;
@@ -107,6 +421,289 @@ impl<'a, Output: Write> App<'a, Output> {
         Ok(())
     }
 
+    /// Updates the value of the given parameter.
+    ///
+    /// Returns the name of the parameter if it is updated.
+    ///
+    /// Returns an error if the value is not set while a transaction is in progress.
+    fn update_parameter(&mut self, parameter: &str, new_value: &str) -> Result<Option<String>> {
+        if let Some(plan) = self.plan.as_mut() {
+            plan.push(PlannedChange::new(self.sysctl, parameter, new_value));
+            return Ok(None);
+        }
+        let param_name = self.set_parameter(parameter, new_value)?;
+        if param_name.is_none()
+            && self.transaction.is_some()
+            && !(self.sysctl.config.cli.ignore_errors
+                && self.sysctl.get_parameters(parameter).is_empty())
+        {
+            return Err(Error::TransactionError(format!(
+                "{parameter}: value not set"
+            )));
+        }
+        Ok(param_name)
+    }
+
+    /// Sets the value of the given parameter and records the change if a transaction is in progress.
+    fn set_parameter(&mut self, parameter: &str, new_value: &str) -> Result<Option<String>> {
+        let parameters = self.sysctl.get_parameters(parameter);
+        if parameters.len() != 1 {
+            log::error!(
+                "{}: ambiguous parameter name: {}",
+                env!("CARGO_PKG_NAME"),
+                parameter
+            );
+            return Ok(None);
+        }
+        let param_name = parameters[0].name.to_string();
+        if DEPRECATED_PARAMS.contains(&parameters[0].get_absolute_name().unwrap_or_default()) {
+            log::error!(
+                "{}: {} is deprecated, value not set",
+                env!("CARGO_PKG_NAME"),
+                parameter
+            );
+            return Ok(None);
+        }
+        let config = self.sysctl.config.clone();
+        if let Some(param) = self
+            .sysctl
+            .parameters
+            .iter_mut()
+            .find(|p| p.name == param_name)
+        {
+            let old_value = param.value.clone();
+            param.update_value(
+                new_value,
+                self.source.clone(),
+                self.sysctl.backend.as_ref(),
+                &config,
+                self.output,
+            )?;
+            if let Some(transaction) = self.transaction.as_mut() {
+                transaction.record(&param.name, &old_value, &param.value);
+            }
+            return Ok(Some(param_name));
+        }
+        Ok(None)
+    }
+
+    /// Runs the given function for applying the values in the given mode.
+    pub fn apply<F>(&mut self, apply_mode: ApplyMode, apply_fn: F) -> Result<()>
+    where
+        F: FnOnce(&mut Self) -> Result<()>,
+    {
+        match apply_mode {
+            ApplyMode::Default => apply_fn(self),
+            ApplyMode::Atomic => {
+                let transaction = self.apply_in_transaction(apply_fn)?;
+                log::debug!("{} change(s) committed", transaction.changes.len());
+                Ok(())
+            }
+            ApplyMode::RevertAfter(timeout) | ApplyMode::Trial(timeout) => {
+                self.apply_with_revert(apply_mode, timeout, apply_fn)
+            }
+            ApplyMode::DryRun => self.apply_dry_run(apply_fn),
+        }
+    }
+
+    /// Runs the given function without writing the values and displays the planned changes.
+    ///
+    /// Returns an error if any of the values cannot be set.
+    fn apply_dry_run<F>(&mut self, apply_fn: F) -> Result<()>
+    where
+        F: FnOnce(&mut Self) -> Result<()>,
+    {
+        self.plan = Some(Vec::new());
+        let result = apply_fn(self);
+        let plan = self.plan.take().unwrap_or_default();
+        match self.sysctl.config.cli.output_type {
+            OutputType::Json => {
+                PlannedChange::display_bulk_json(&plan, self.output)?;
+            }
+            _ => {
+                plan.iter()
+                    .try_for_each(|change| change.display(&self.sysctl.config, self.output))?;
+            }
+        }
+        result?;
+        let errors = plan
+            .iter()
+            .filter(|change| change.status.is_error())
+            .count();
+        if errors != 0 {
+            return Err(Error::DryRunError(format!(
+                "{errors} value(s) cannot be set"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Runs the given function in a transaction.
+    ///
+    /// The changes are rolled back in the reverse order when the function fails.
+    fn apply_in_transaction<F>(&mut self, apply_fn: F) -> Result<Transaction>
+    where
+        F: FnOnce(&mut Self) -> Result<()>,
+    {
+        self.transaction = Some(Transaction::default());
+        let result = apply_fn(self);
+        let transaction = self.transaction.take().unwrap_or_default();
+        if let Err(e) = result {
+            log::error!("{}: {}", env!("CARGO_PKG_NAME"), e);
+            let (rolled_back, failed) = self.rollback(transaction)?;
+            return Err(Error::TransactionError(format!(
+                "{rolled_back} change(s) rolled back, {failed} failed"
+            )));
+        }
+        Ok(transaction)
+    }
+
+    /// Rolls back the given transaction and reports the restored values.
+    ///
+    /// Returns the number of the rolled back and failed changes.
+    fn rollback(&mut self, transaction: Transaction) -> Result<(usize, usize)> {
+        let mut failed = 0;
+        let mut output_result = Ok(());
+        let results = transaction.rollback(self.sysctl);
+        for (change, result) in &results {
+            match result {
+                Ok(()) => {
+                    output_result = output_result.and(writeln!(
+                        self.output,
+                        "{} = {} (rolled back from {})",
+                        change.name, change.old_value, change.new_value
+                    ));
+                }
+                Err(e) => {
+                    log::error!(
+                        "{}: cannot roll back {}: {}",
+                        env!("CARGO_PKG_NAME"),
+                        change.name,
+                        e
+                    );
+                    failed += 1;
+                }
+            }
+        }
+        output_result?;
+        Ok((results.len() - failed, failed))
+    }
+
+    /// Runs the given function in a transaction and rolls back the changes after the timeout.
+    ///
+    /// The changes are kept if they are confirmed interactively or via `--confirm`
+    /// before the timeout. Interrupting the process rolls back the changes immediately.
+    fn apply_with_revert<F>(
+        &mut self,
+        apply_mode: ApplyMode,
+        timeout: Duration,
+        apply_fn: F,
+    ) -> Result<()>
+    where
+        F: FnOnce(&mut Self) -> Result<()>,
+    {
+        let path = PendingTransaction::get_path();
+        if let Some(pending) = PendingTransaction::from_file(&path)
+            .ok()
+            .filter(|pending| pending.is_active())
+        {
+            return Err(Error::TransactionError(format!(
+                "another change is pending confirmation (pid: {})",
+                pending.pid
+            )));
+        }
+        let transaction = self.apply_in_transaction(apply_fn)?;
+        if transaction.changes.is_empty() {
+            return Ok(());
+        }
+        let pending = PendingTransaction {
+            pid: process::id(),
+            deadline: (SystemTime::now() + timeout)
+                .duration_since(SystemTime::UNIX_EPOCH)?
+                .as_secs(),
+            transaction,
+        };
+        if let Err(e) = pending.save_to_file(&path) {
+            self.rollback(pending.transaction)?;
+            return Err(e);
+        }
+        handle_signals();
+        let interactive =
+            matches!(apply_mode, ApplyMode::RevertAfter(_)) && io::stdin().is_terminal();
+        if interactive {
+            write!(
+                self.output,
+                "Keep the changes? [y/N] (reverting in {}s or confirm via `{} --confirm`) ",
+                timeout.as_secs(),
+                env!("CARGO_PKG_NAME")
+            )?;
+            self.output.flush()?;
+        } else {
+            eprintln!(
+                "{}: reverting in {}s unless confirmed via `{} --confirm`",
+                env!("CARGO_PKG_NAME"),
+                timeout.as_secs(),
+                env!("CARGO_PKG_NAME")
+            );
+        }
+        let confirmed = wait_for_confirmation(&path, timeout, interactive);
+        let remove_result = match fs::remove_file(&path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        };
+        if confirmed {
+            remove_result?;
+            if !self.sysctl.config.cli.quiet {
+                writeln!(
+                    self.output,
+                    "{} change(s) confirmed",
+                    pending.transaction.changes.len()
+                )?;
+            }
+            return Ok(());
+        }
+        // The output may not be available anymore (e.g. after a hangup)
+        // so its errors are reported after the rollback.
+        let output_result = if interactive {
+            writeln!(self.output)
+        } else {
+            Ok(())
+        };
+        let (rolled_back, failed) = self.rollback(pending.transaction)?;
+        remove_result?;
+        output_result?;
+        match apply_mode {
+            ApplyMode::Trial(_) if failed == 0 => Ok(()),
+            ApplyMode::Trial(_) => Err(Error::TransactionError(format!(
+                "{rolled_back} change(s) rolled back, {failed} failed"
+            ))),
+            _ => Err(Error::TransactionError(format!(
+                "changes are not confirmed, {rolled_back} change(s) rolled back, {failed} failed"
+            ))),
+        }
+    }
+
+    /// Confirms the changes that are pending confirmation in another session.
+    pub fn confirm_pending(&mut self) -> Result<()> {
+        let path = PendingTransaction::get_path();
+        let pending = PendingTransaction::from_file(&path)
+            .ok()
+            .filter(|pending| pending.is_active())
+            .ok_or_else(|| {
+                Error::TransactionError(String::from("no changes are pending confirmation"))
+            })?;
+        fs::remove_file(&path)?;
+        if !self.sysctl.config.cli.quiet {
+            writeln!(
+                self.output,
+                "{} change(s) confirmed (pid: {})",
+                pending.transaction.changes.len(),
+                pending.pid
+            )?;
+        }
+        Ok(())
+    }
+
     /// Updates the parameter if it has the format `name=value`, displays it otherwise.
     pub fn process_parameter(
         &mut self,
@@ -124,33 +721,8 @@ impl<'a, Output: Write> App<'a, Output> {
         } else {
             None
         };
-        let sysctl = self.sysctl.clone();
         if let Some(new_value) = new_value {
-            let parameters = sysctl.get_parameters(&parameter);
-            if parameters.len() == 1 {
-                let param = parameters[0];
-                if DEPRECATED_PARAMS.contains(&param.get_absolute_name().unwrap_or_default()) {
-                    log::error!(
-                        "{}: {} is deprecated, value not set",
-                        env!("CARGO_PKG_NAME"),
-                        parameter
-                    );
-                } else if let Some(param) = self
-                    .sysctl
-                    .parameters
-                    .iter_mut()
-                    .find(|p| p.name == param.name)
-                {
-                    let config = self.sysctl.config.clone();
-                    param.update_value(&new_value, &config, self.output)?;
-                }
-            } else {
-                log::error!(
-                    "{}: ambiguous parameter name: {}",
-                    env!("CARGO_PKG_NAME"),
-                    parameter
-                );
-            }
+            self.update_parameter(&parameter, &new_value)?;
         } else if write_mode {
             log::error!(
                 "{}: {:?} must be in the format: name=value",
@@ -158,19 +730,72 @@ impl<'a, Output: Write> App<'a, Output> {
                 parameter
             );
         } else if display_value {
+            let sysctl = self.sysctl.clone();
             let parameters = sysctl.get_parameters(&parameter);
             self.print_parameters(&mut parameters.into_iter())?;
         }
         Ok(())
     }
 
+    /// Updates the parameter in the format `name=value` and saves the value to the given file.
+    ///
+    /// Warns if the saved value is overridden by another file in the system directories.
+    pub fn save_parameter(&mut self, parameter: String, save_path: PathBuf) -> Result<()> {
+        let Some((param_name, new_value)) = parameter
+            .split_once('=')
+            .map(|(name, value)| (name.trim(), value.trim()))
+        else {
+            log::error!(
+                "{}: {:?} must be in the format: name=value",
+                env!("CARGO_PKG_NAME"),
+                parameter
+            );
+            return Ok(());
+        };
+        let Some(param_name) = self.update_parameter(param_name, new_value)? else {
+            return Ok(());
+        };
+        let save_path = self.sysctl.save_to_file(
+            param_name.to_string(),
+            new_value.to_string(),
+            &Some(save_path),
+        )?;
+        log::debug!("{}: saved to {}", param_name, save_path.display());
+        let files = preload::get_preload_files(SYSTEM_PRELOAD);
+        match preload::get_overrides(&files, &save_path, &param_name) {
+            Some(overrides) => {
+                for assignment in overrides {
+                    log::warn!(
+                        "{}: {} is overridden at boot by {}:{}",
+                        env!("CARGO_PKG_NAME"),
+                        param_name,
+                        assignment.path.display(),
+                        assignment.line
+                    );
+                }
+            }
+            None => {
+                log::warn!(
+                    "{}: {} is not applied at boot",
+                    env!("CARGO_PKG_NAME"),
+                    save_path.display()
+                );
+            }
+        }
+        Ok(())
+    }
+
     /// Processes the parameters in the given file.
     pub fn preload_from_file(&mut self, path: PathBuf) -> Result<()> {
-        if path == PathBuf::from("-") {
+        self.source = ChangeSource::File(path.clone());
+        if path.as_os_str() == "-" {
             let stdin = io::stdin();
             let lines = stdin.lock().lines();
             for line in lines {
                 if let Err(e) = self.process_parameter(line?, true, false) {
+                    if self.transaction.is_some() {
+                        return Err(e);
+                    }
                     log::info!("{}: {}", env!("CARGO_PKG_NAME"), e);
                 }
             }
@@ -200,21 +825,595 @@ impl<'a, Output: Write> App<'a, Output> {
         Ok(())
     }
 
+    /// Saves a snapshot of the parameters to the given file.
+    ///
+    /// Prints the snapshot if the path is `-`.
+    pub fn save_snapshot(&mut self, path: PathBuf) -> Result<()> {
+        let snapshot = Snapshot::new(self.sysctl)?;
+        if path.as_os_str() == "-" {
+            snapshot.write(self.output)
+        } else {
+            snapshot.save_to_file(&path)?;
+            if !self.sysctl.config.cli.quiet {
+                writeln!(
+                    self.output,
+                    "{} parameters saved to {}",
+                    snapshot.parameters.len(),
+                    path.display()
+                )?;
+            }
+            Ok(())
+        }
+    }
+
+    /// Restores the parameters from the given snapshot file.
+    ///
+    /// Only the writable parameters that differ from the snapshot are updated and the
+    /// first error aborts the restore in a transaction.
+    /// Reads the snapshot from stdin if the path is `-`.
+    pub fn restore_snapshot(&mut self, path: PathBuf) -> Result<()> {
+        let snapshot = if path.as_os_str() == "-" {
+            Snapshot::parse(&io::read_to_string(io::stdin())?)?
+        } else {
+            Snapshot::from_file(&path)?
+        };
+        self.source = ChangeSource::File(path.clone());
+        let mut failed = 0;
+        for (name, value) in &snapshot.parameters {
+            let Some(param) = self.sysctl.parameters.iter().find(|p| &p.name == name) else {
+                log::error!(
+                    "{}: {}: unknown parameter, value not restored",
+                    env!("CARGO_PKG_NAME"),
+                    name
+                );
+                failed += 1;
+                continue;
+            };
+            if param.value == ParameterValue::from(value.as_str()) {
+                continue;
+            }
+            if !param.access.writable {
+                log::debug!(
+                    "{}: {} is not writable, skipping",
+                    env!("CARGO_PKG_NAME"),
+                    name
+                );
+                continue;
+            }
+            match self.update_parameter(name, value) {
+                Ok(Some(_)) => {}
+                Ok(None) if self.plan.is_some() => {}
+                Ok(None) => failed += 1,
+                Err(e) if self.transaction.is_some() => return Err(e),
+                Err(e) => {
+                    log::error!("{}: {}: {}", env!("CARGO_PKG_NAME"), name, e);
+                    failed += 1;
+                }
+            }
+        }
+        if failed != 0 {
+            return Err(Error::SnapshotError(format!(
+                "{failed} parameter(s) could not be restored"
+            )));
+        }
+        Ok(())
+    }
+
     /// Processes the parameters in files that are in predefined system directories.
+    ///
+    /// Files and parameters are resolved with the `systemd-sysctl` semantics.
     pub fn preload_from_system(&mut self) -> Result<()> {
-        for preload_path in SYSTEM_PRELOAD
+        let files = preload::get_preload_files(SYSTEM_PRELOAD);
+        for file in &files {
+            log::info!("* Applying {} ...", file.display());
+        }
+        let assignments = preload::resolve(&files, self.sysctl);
+        self.preload_assignments(assignments)
+    }
+
+    /// Sets the values of the given assignments.
+    ///
+    /// Failures are reported with the file and line of the assignment.
+    fn preload_assignments(&mut self, assignments: Vec<Assignment>) -> Result<()> {
+        let mut failed = 0;
+        for assignment in assignments {
+            let Some(value) = assignment.value else {
+                continue;
+            };
+            if assignment.ignore_failure
+                && !self
+                    .sysctl
+                    .parameters
+                    .iter()
+                    .any(|parameter| parameter.name == assignment.name)
+            {
+                log::debug!("Skipping unknown parameter: {}", assignment.name);
+                continue;
+            }
+            self.source = ChangeSource::File(assignment.path.clone());
+            if let Err(e) =
+                self.process_parameter(format!("{}={}", assignment.name, value), false, false)
+            {
+                if assignment.ignore_failure {
+                    log::debug!("{}: {}", assignment.name, e);
+                } else if self.transaction.is_some() {
+                    log::error!(
+                        "{}: {}:{}",
+                        env!("CARGO_PKG_NAME"),
+                        assignment.path.display(),
+                        assignment.line
+                    );
+                    return Err(e);
+                } else {
+                    log::error!(
+                        "{}: {}:{}: {}",
+                        env!("CARGO_PKG_NAME"),
+                        assignment.path.display(),
+                        assignment.line,
+                        e
+                    );
+                    failed += 1;
+                }
+            }
+        }
+        if failed != 0 {
+            return Err(Error::PreloadError(format!(
+                "{failed} parameter(s) could not be set"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Displays the assignments of the given parameter in the system directories and
+    /// on the kernel command line.
+    ///
+    /// The assignment that is applied at last is marked with `*`.
+    pub fn display_origin(&mut self, param_name: &str) -> Result<()> {
+        let cmdline_path = Path::new(KERNEL_CMDLINE_PATH);
+        let cmdline_assignments = match reader::read_to_string(cmdline_path) {
+            Ok(cmdline) => origin::parse_cmdline(&cmdline, cmdline_path),
+            Err(e) => {
+                log::debug!("Cannot read the kernel command line: {}", e);
+                Vec::new()
+            }
+        };
+        let origins = Origin::find(
+            &cmdline_assignments,
+            &preload::get_preload_files(SYSTEM_PRELOAD),
+            &preload::get_overridden_files(SYSTEM_PRELOAD),
+            param_name,
+        );
+        match self.sysctl.config.cli.output_type {
+            OutputType::Json => {
+                Origin::display_bulk_json(&origins, self.output)?;
+            }
+            _ => {
+                if origins.is_empty() {
+                    log::warn!(
+                        "{}: {} is not assigned in any of the system files",
+                        env!("CARGO_PKG_NAME"),
+                        param_name
+                    );
+                }
+                origins
+                    .iter()
+                    .try_for_each(|origin| origin.display(&self.sysctl.config, self.output))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Displays the contents of the files in the system directories in the order they are applied.
+    pub fn display_system_files(&mut self) -> Result<()> {
+        for (i, file) in preload::get_preload_files(SYSTEM_PRELOAD)
             .iter()
-            .map(|v| PathBuf::from(v).join("*.conf"))
+            .enumerate()
         {
-            if let Ok(glob_walker) = globwalk::glob(preload_path.to_string_lossy()) {
-                for file in glob_walker.filter_map(|v| v.ok()) {
-                    log::info!("* Applying {} ...", file.path().display());
-                    self.preload_from_file(file.path().to_path_buf())?;
+            let contents = reader::read_to_string(file)?;
+            if i != 0 {
+                writeln!(self.output)?;
+            }
+            writeln!(self.output, "# {}", file.display())?;
+            write!(self.output, "{contents}")?;
+            if !contents.is_empty() && !contents.ends_with('\n') {
+                writeln!(self.output)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the journal of the changes.
+    fn get_journal(&self) -> Result<Journal> {
+        self.sysctl
+            .config
+            .journal_path
+            .as_ref()
+            .map(Journal::new)
+            .ok_or_else(|| Error::JournalError(String::from("journal path is not set")))
+    }
+
+    /// Displays the changes in the journal.
+    pub fn display_history(&mut self, pattern: Option<Regex>) -> Result<()> {
+        let entries = self
+            .get_journal()?
+            .read()?
+            .into_iter()
+            .filter(|entry| {
+                pattern
+                    .as_ref()
+                    .is_none_or(|pattern| pattern.is_match(&entry.name))
+            })
+            .collect::<Vec<JournalEntry>>();
+        match self.sysctl.config.cli.output_type {
+            OutputType::Json => {
+                JournalEntry::display_bulk_json(&entries, self.output)?;
+            }
+            _ => {
+                entries
+                    .iter()
+                    .try_for_each(|entry| entry.display(&self.sysctl.config, self.output))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Restores the old values of the given number of changes in the journal.
+    ///
+    /// Warns if a parameter is changed since it is recorded in the journal.
+    pub fn undo(&mut self, count: usize) -> Result<()> {
+        let entries = self.get_journal()?.get_undoable(count)?;
+        if entries.is_empty() {
+            log::warn!("{}: nothing to undo", env!("CARGO_PKG_NAME"));
+        }
+        self.source = ChangeSource::Undo;
+        for entry in entries {
+            if let Some(parameter) = self
+                .sysctl
+                .parameters
+                .iter()
+                .find(|parameter| parameter.name == entry.name)
+            {
+                if parameter.value != ParameterValue::from(entry.new_value.as_str()) {
+                    log::warn!(
+                        "{}: {} is changed to {} after it is set to {}",
+                        env!("CARGO_PKG_NAME"),
+                        entry.name,
+                        parameter.value.to_string().replace('\n', " "),
+                        entry.new_value.replace('\n', " ")
+                    );
+                }
+            }
+            self.update_parameter(&entry.name, &entry.old_value)?;
+        }
+        Ok(())
+    }
+
+    /// Watches the given parameters and displays the changes of their values.
+    ///
+    /// Parameters are filtered by the given pattern.
+    /// The given command is run for each change with the change in its environment.
+    pub fn watch(
+        &mut self,
+        pattern: Option<Regex>,
+        interval: Duration,
+        exec: Option<String>,
+    ) -> Result<()> {
+        if let Some(pattern) = pattern.as_ref().filter(|pattern| {
+            !self
+                .sysctl
+                .parameters
+                .iter()
+                .any(|p| pattern.is_match(&p.name))
+        }) {
+            return Err(Error::WatchError(format!(
+                "no parameters found matching {:?}",
+                pattern.as_str()
+            )));
+        }
+        loop {
+            thread::sleep(interval);
+            let changes = self.sysctl.refresh(|parameter| {
+                pattern
+                    .as_ref()
+                    .is_none_or(|pattern| pattern.is_match(&parameter.name))
+            });
+            for change in changes {
+                let event = WatchEvent::new(change)?;
+                match self.sysctl.config.cli.output_type {
+                    OutputType::Json => event.display_json(self.output)?,
+                    _ => event.display(&self.sysctl.config, self.output)?,
+                }
+                self.output.flush()?;
+                if let Some(command) = &exec {
+                    Self::run_hook(command, &event.change);
+                }
+            }
+        }
+    }
+
+    /// Runs the given command for the given change.
+    ///
+    /// The name and values are passed as `SYSTEROID_NAME`, `SYSTEROID_OLD_VALUE` and
+    /// `SYSTEROID_NEW_VALUE` environment variables.
+    fn run_hook(command: &str, change: &Change) {
+        match Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .env("SYSTEROID_NAME", &change.name)
+            .env("SYSTEROID_OLD_VALUE", &change.old_value)
+            .env("SYSTEROID_NEW_VALUE", &change.new_value)
+            .status()
+        {
+            Ok(status) if !status.success() => {
+                log::error!(
+                    "{}: {:?} failed for {} ({})",
+                    env!("CARGO_PKG_NAME"),
+                    command,
+                    change.name,
+                    status
+                );
+            }
+            Ok(_) => {}
+            Err(e) => {
+                log::error!(
+                    "{}: cannot run {:?}: {}",
+                    env!("CARGO_PKG_NAME"),
+                    command,
+                    e
+                );
+            }
+        }
+    }
+
+    /// Displays the given profile along with the values of the included profiles.
+    pub fn display_profile(&mut self, name: &str) -> Result<()> {
+        let profile = Profile::find(name, &self.sysctl.config)?;
+        match self.sysctl.config.cli.output_type {
+            OutputType::Json => profile.display_json(&self.sysctl.config, self.output),
+            _ => profile.display(&self.sysctl.config, self.output),
+        }
+    }
+
+    /// Displays the differences between the current values and the given profile.
+    pub fn display_profile_diff(&mut self, name: &str, pattern: Option<Regex>) -> Result<()> {
+        let profile = Profile::find(name, &self.sysctl.config)?;
+        let assignments = preload::resolve(&profile.get_files(&self.sysctl.config)?, self.sysctl);
+        let new_values = assignments
+            .into_iter()
+            .filter(|assignment| {
+                pattern
+                    .as_ref()
+                    .is_none_or(|pattern| pattern.is_match(&assignment.name))
+            })
+            .filter_map(|assignment| Some((assignment.name, assignment.value?)))
+            .collect::<ParameterValues>();
+        let old_values = self
+            .sysctl
+            .parameters
+            .iter()
+            .filter(|parameter| new_values.contains_key(&parameter.name))
+            .map(|parameter| (parameter.name.to_string(), parameter.value.to_string()))
+            .collect::<ParameterValues>();
+        self.print_diffs(&ParameterDiff::compute(&old_values, &new_values))
+    }
+
+    /// Applies the values in the given profile along with the included profiles.
+    pub fn apply_profile(&mut self, name: &str) -> Result<()> {
+        let profile = Profile::find(name, &self.sysctl.config)?;
+        let files = profile.get_files(&self.sysctl.config)?;
+        for file in &files {
+            log::info!("* Applying {} ...", file.display());
+        }
+        let assignments = preload::resolve(&files, self.sysctl);
+        self.preload_assignments(assignments)
+    }
+
+    /// Compares the values in the system directories with the current values.
+    ///
+    /// Returns an error if any of the parameters drifted.
+    pub fn check_drift(&mut self) -> Result<()> {
+        let assignments =
+            preload::resolve(&preload::get_preload_files(SYSTEM_PRELOAD), self.sysctl);
+        let drifts = Drift::check(self.sysctl, &assignments);
+        match self.sysctl.config.cli.output_type {
+            OutputType::Json => {
+                Drift::display_bulk_json(&drifts, self.output)?;
+            }
+            _ => {
+                drifts
+                    .iter()
+                    .try_for_each(|drift| drift.display(&self.sysctl.config, self.output))?;
+            }
+        }
+        if !drifts.is_empty() {
+            return Err(Error::DriftError(format!(
+                "{} parameter(s) differ from the system configuration",
+                drifts.len()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Serves the numeric values of the matching parameters in Prometheus format over HTTP.
+    ///
+    /// Values are re-read for each request to `/metrics`. Connections are handled in
+    /// separate threads and the requests are limited in size and reading time.
+    pub fn serve_metrics(&mut self, address: &str, query: &Query) -> Result<()> {
+        let listener = TcpListener::bind(address)?;
+        writeln!(
+            self.output,
+            "Serving metrics on http://{}/metrics",
+            listener.local_addr()?
+        )?;
+        self.output.flush()?;
+        let sysctl = Mutex::new(&mut *self.sysctl);
+        let connections = AtomicUsize::new(0);
+        thread::scope(|scope| {
+            for stream in listener.incoming() {
+                let stream = match stream {
+                    Ok(stream) => stream,
+                    Err(e) => {
+                        log::error!(
+                            "{}: cannot accept the connection: {}",
+                            env!("CARGO_PKG_NAME"),
+                            e
+                        );
+                        continue;
+                    }
+                };
+                if connections.fetch_add(1, Ordering::SeqCst) >= METRICS_MAX_CONNECTIONS {
+                    connections.fetch_sub(1, Ordering::SeqCst);
+                    log::warn!(
+                        "{}: too many connections, dropping the request",
+                        env!("CARGO_PKG_NAME")
+                    );
+                    continue;
                 }
+                let (sysctl, connections) = (&sysctl, &connections);
+                scope.spawn(move || {
+                    if let Err(e) = Self::handle_metrics_request(stream, sysctl, query) {
+                        log::error!(
+                            "{}: cannot handle the request: {}",
+                            env!("CARGO_PKG_NAME"),
+                            e
+                        );
+                    }
+                    connections.fetch_sub(1, Ordering::SeqCst);
+                });
+            }
+        });
+        Ok(())
+    }
+
+    /// Responds to an HTTP request for the metrics.
+    fn handle_metrics_request(
+        stream: TcpStream,
+        sysctl: &Mutex<&mut Sysctl>,
+        query: &Query,
+    ) -> Result<()> {
+        let mut request_line = String::new();
+        {
+            let mut reader = BufReader::new(
+                DeadlineStream {
+                    stream: &stream,
+                    deadline: Instant::now() + METRICS_READ_TIMEOUT,
+                }
+                .take(METRICS_MAX_REQUEST_SIZE),
+            );
+            reader.read_line(&mut request_line)?;
+            let mut header = String::new();
+            while reader.read_line(&mut header)? != 0 && !header.trim().is_empty() {
+                header.clear();
+            }
+        }
+        let mut fields = request_line.split_whitespace();
+        let method = fields.next().unwrap_or_default();
+        let (status, content_type, body) = match (method, fields.next()) {
+            ("GET" | "HEAD", Some("/metrics")) => {
+                // the values are filtered after they are re-read
+                let uses_value = query.uses(Field::Value);
+                let mut sysctl = sysctl.lock().unwrap_or_else(PoisonError::into_inner);
+                sysctl.refresh(|parameter| uses_value || query.matches(parameter));
+                let mut body = Vec::new();
+                metrics::write_metrics(
+                    sysctl
+                        .parameters
+                        .iter()
+                        .filter(|parameter| query.matches(parameter)),
+                    &mut body,
+                )?;
+                ("200 OK", metrics::CONTENT_TYPE, body)
+            }
+            ("GET" | "HEAD", _) => ("404 Not Found", "text/plain", b"Not Found\n".to_vec()),
+            _ => (
+                "405 Method Not Allowed",
+                "text/plain",
+                b"Method Not Allowed\n".to_vec(),
+            ),
+        };
+        let mut writer = DeadlineStream {
+            stream: &stream,
+            deadline: Instant::now() + METRICS_WRITE_TIMEOUT,
+        };
+        write!(
+            writer,
+            "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            status,
+            content_type,
+            body.len()
+        )?;
+        if method != "HEAD" {
+            writer.write_all(&body)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Saves the numeric values of the matching parameters to a file for the
+    /// textfile collector of the node exporter.
+    pub fn save_metrics(&mut self, path: PathBuf, query: &Query) -> Result<()> {
+        metrics::save_metrics(
+            self.sysctl
+                .parameters
+                .iter()
+                .filter(|parameter| query.matches(parameter)),
+            &path,
+        )
+    }
+
+    /// Audits the parameters against the rules in the given file or the bundled rules.
+    ///
+    /// Returns an error if any of the checks fail.
+    pub fn audit(&mut self, rules_path: Option<PathBuf>, report_type: ReportType) -> Result<()> {
+        let rules = AuditRule::load(rules_path.as_deref())?;
+        let report = AuditReport::new(self.sysctl, rules);
+        match report_type {
+            ReportType::Json => report.display_json(self.output)?,
+            ReportType::Junit => report.display_junit(self.output)?,
+            ReportType::Sarif => report.display_sarif(self.output)?,
+            ReportType::Default => report.display(&self.sysctl.config, self.output)?,
+            ReportType::Csv => {
+                return Err(Error::AuditError(String::from(
+                    "CSV report is not supported",
+                )))
             }
         }
+        let failures = report.count(AuditStatus::Fail);
+        if failures != 0 {
+            return Err(Error::AuditError(format!(
+                "{} of {} check(s) failed",
+                failures,
+                report.results.len()
+            )));
+        }
         Ok(())
     }
+
+    /// Compares the dumps of the hosts in the given directory and displays the parameters
+    /// that are not identical across them.
+    pub fn display_fleet_report(
+        &mut self,
+        dir: PathBuf,
+        pattern: Option<Regex>,
+        report_type: ReportType,
+        include_volatile: bool,
+    ) -> Result<()> {
+        let mut hosts = fleet::read_hosts(&dir)?;
+        if let Some(pattern) = &pattern {
+            for (_, values) in hosts.iter_mut() {
+                values.retain(|name, _| pattern.is_match(name));
+            }
+        }
+        let report = FleetReport::new(hosts, include_volatile);
+        match report_type {
+            ReportType::Json => report.display_json(self.output),
+            ReportType::Csv => report.display_csv(self.output),
+            ReportType::Default => report.display(&self.sysctl.config, self.output),
+            ReportType::Junit | ReportType::Sarif => Err(Error::FleetError(format!(
+                "{report_type} report is not supported"
+            ))),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -233,14 +1432,14 @@ mod tests {
 
         let mut app = App::new(&mut sysctl, &mut output);
 
-        app.display_parameters(Regex::new("kernel|vm").ok(), false)?;
+        app.display_parameters(&"name:~kernel|vm".parse()?, false)?;
         let result = String::from_utf8_lossy(app.output);
         assert!(result.contains("vm.zone_reclaim_mode ="));
         assert!(result.contains("kernel.version ="));
         app.output.clear();
 
         app.sysctl.config.cli.output_type = OutputType::Tree;
-        app.display_parameters(None, false)?;
+        app.display_parameters(&Query::All, false)?;
         assert!(String::from_utf8_lossy(app.output).contains("─ osrelease ="));
         app.output.clear();
 
@@ -1,22 +1,37 @@
+use crate::app::{ApplyMode, ProfileAction};
 use getopts::Options;
 use parseit::regex::Regex;
 use std::env;
 use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime};
 use systeroid_core::config::CONFIG_ENV;
+use systeroid_core::sysctl::access::AccessFilter;
+use systeroid_core::sysctl::export::ExportFormat;
+use systeroid_core::sysctl::namespace::NamespaceTarget;
+use systeroid_core::sysctl::query::Query;
 use systeroid_core::sysctl::r#type::DisplayType;
 use systeroid_core::sysctl::r#type::OutputType;
-use systeroid_core::sysctl::{DEFAULT_PRELOAD, KERNEL_DOCS_ENV};
+use systeroid_core::sysctl::r#type::ReportType;
+use systeroid_core::sysctl::source::Source;
+use systeroid_core::sysctl::{DEFAULT_PRELOAD, DEFAULT_SAVE_PATH, KERNEL_DOCS_ENV};
 
 /// Help message for the arguments.
 const HELP_MESSAGE: &str = r#"
 Usage:
     {bin} [options] [variable[=value] ...] --load[=<file>]
+    {bin} [options] variable=value ... --save[=<file>]
 
 Options:
 {usage}
 
 For more details see {bin}(8)."#;
 
+/// Options that take an optional argument.
+///
+/// getopts only binds the argument in the `--name=value` form so it is taken from
+/// the free arguments otherwise (e.g. `--undo 3`).
+const OPTIONAL_ARG_OPTIONS: &[&str] = &["history", "undo", "watch", "audit"];
+
 /// Command-line arguments.
 #[derive(Debug, Default)]
 pub struct Args {
@@ -30,6 +45,10 @@ pub struct Args {
     pub write: bool,
     /// Path of the Linux kernel documentation.
     pub kernel_docs: Option<PathBuf>,
+    /// Root directory of the kernel parameters.
+    pub root: Option<PathBuf>,
+    /// Path of the captured values to read the variables from.
+    pub from: Option<PathBuf>,
     /// Display type of the variables.
     pub display_type: DisplayType,
     /// Whether if the deprecated variables should be included while listing.
@@ -42,8 +61,64 @@ pub struct Args {
     pub preload_files: bool,
     /// Whether if the values will be preloaded from system.
     pub preload_system_files: bool,
+    /// Mode for applying the values.
+    pub apply_mode: ApplyMode,
+    /// Whether if the pending changes should be confirmed.
+    pub confirm: bool,
+    /// Whether if the values in the system directories should be compared with the current values.
+    pub check_drift: bool,
+    /// Name of the variable to show the origins of.
+    pub origin: Option<String>,
+    /// Whether if the system configuration files should be printed.
+    pub cat_config: bool,
+    /// Path of the file to save the values to.
+    pub save: Option<PathBuf>,
+    /// Whether if the journal of the changes should be displayed.
+    pub history: bool,
+    /// Number of the changes to undo.
+    pub undo: Option<usize>,
+    /// Whether if the changes of the variables should be watched.
+    pub watch: bool,
+    /// Interval for checking the changes.
+    pub interval: Option<Duration>,
+    /// Command to run for each change.
+    pub exec: Option<String>,
+    /// Name of the profile.
+    pub profile: Option<String>,
+    /// Action to perform on the profile.
+    pub profile_action: ProfileAction,
+    /// Whether if the variables should be audited.
+    pub audit: bool,
+    /// Path of the audit rules.
+    pub audit_rules: Option<PathBuf>,
+    /// Format of the audit or fleet report.
+    pub report_type: ReportType,
+    /// Directory of the host dumps to compare.
+    pub fleet_report: Option<PathBuf>,
+    /// Whether if the volatile variables should be compared in the fleet report.
+    pub include_volatile: bool,
+    /// Address to serve the metrics on.
+    pub serve_metrics: Option<String>,
+    /// Path of the file to save the metrics to.
+    pub metrics_file: Option<PathBuf>,
+    /// Format for exporting the variables to a container runtime.
+    pub export: Option<ExportFormat>,
+    /// Path of the snapshot file to save.
+    pub snapshot: Option<PathBuf>,
+    /// Path of the snapshot file to restore.
+    pub restore: Option<PathBuf>,
+    /// Sources to compare.
+    pub diff: Option<(Source, Source)>,
+    /// Namespace to read and write the variables from.
+    pub target_namespace: Option<NamespaceTarget>,
+    /// Network namespaces to compare.
+    pub compare_netns: Option<(String, String)>,
     /// Pattern for matching the variables.
     pub pattern: Option<Regex>,
+    /// Filter for matching the variables based on their access permissions.
+    pub access_filter: Option<AccessFilter>,
+    /// Query for matching the variables.
+    pub query: Option<Query>,
     /// Whether if the documentation should be shown.
     pub explain: bool,
     /// Output type of the application.
@@ -54,6 +129,27 @@ pub struct Args {
     pub values: Vec<String>,
 }
 
+/// Parses a duration in seconds, or with one of the `s`, `m` and `h` suffixes.
+///
+/// Durations that cannot be added to the current time are rejected.
+fn parse_duration(value: &str) -> Option<Duration> {
+    let (amount, multiplier) = match value.char_indices().last()? {
+        (i, 's') => (&value[..i], 1),
+        (i, 'm') => (&value[..i], 60),
+        (i, 'h') => (&value[..i], 60 * 60),
+        _ => (value, 1),
+    };
+    amount
+        .parse::<u64>()
+        .ok()
+        .and_then(|amount| amount.checked_mul(multiplier))
+        .map(Duration::from_secs)
+        .filter(|duration| {
+            SystemTime::now().checked_add(*duration).is_some()
+                && Instant::now().checked_add(*duration).is_some()
+        })
+}
+
 impl Args {
     /// Returns the available options.
     fn get_options() -> Options {
@@ -75,12 +171,172 @@ impl Args {
         opts.optflag("p", "load", "read values from file (-f)");
         opts.optflag("f", "", "alias of -p");
         opts.optflag("S", "system", "read values from all system directories");
+        opts.optflag(
+            "",
+            "atomic",
+            "roll back all changes if any of the values cannot be set",
+        );
+        opts.optopt(
+            "",
+            "revert-after",
+            "roll back the changes unless they are confirmed in time",
+            "<secs>",
+        );
+        opts.optopt(
+            "",
+            "for",
+            "roll back the changes after a duration (e.g. 10m)",
+            "<duration>",
+        );
+        opts.optflag("", "confirm", "confirm the changes that are pending");
+        opts.optflag(
+            "",
+            "dry-run",
+            "display the changes without writing the values",
+        );
+        opts.optflag(
+            "",
+            "check-drift",
+            "compare the values in the system directories with the current values",
+        );
+        opts.optopt(
+            "",
+            "origin",
+            "display the files that assign a value to the variable",
+            "<name>",
+        );
+        opts.optflag(
+            "",
+            "cat-config",
+            "display the system configuration files in the order they are applied",
+        );
+        opts.optflagopt(
+            "",
+            "save",
+            "set the variable and save its value to a file",
+            "<file>",
+        );
+        opts.optflagopt(
+            "",
+            "history",
+            "display the journal of the changes (optionally matching a regex)",
+            "<expr>",
+        );
+        opts.optflagopt("", "undo", "undo the last N changes (default: 1)", "<N>");
+        opts.optflagopt(
+            "",
+            "watch",
+            "display the changes of the variables (optionally matching a regex)",
+            "<expr>",
+        );
+        opts.optopt(
+            "",
+            "interval",
+            "set the interval for checking the changes (default: 1000)",
+            "<ms>",
+        );
+        opts.optopt("", "exec", "run a command for each change", "<cmd>");
+        opts.optopt(
+            "",
+            "profile",
+            "use a tuning profile (with --show, --diff or --apply)",
+            "<name>",
+        );
+        opts.optflag("", "show", "display the values in the profile");
+        opts.optflag("", "apply", "apply the values in the profile");
+        opts.optflagopt(
+            "",
+            "audit",
+            "check the variables against the rules in a file (default: bundled rules)",
+            "<file>",
+        );
+        opts.optopt(
+            "",
+            "fleet-report",
+            "compare the dumps of the hosts in a directory",
+            "<dir>",
+        );
+        opts.optflag(
+            "",
+            "include-volatile",
+            "include the host-specific variables (e.g. boot_id) in the fleet report",
+        );
+        opts.optopt(
+            "",
+            "report",
+            "set the format of the audit (table, json, junit, sarif) or fleet report (table, json, csv)",
+            "<format>",
+        );
+        opts.optopt(
+            "",
+            "serve-metrics",
+            "serve the variables in Prometheus format (e.g. 127.0.0.1:9781)",
+            "<addr>",
+        );
+        opts.optopt(
+            "",
+            "metrics-file",
+            "save the variables to a file in Prometheus format",
+            "<file>",
+        );
+        opts.optopt(
+            "",
+            "export",
+            "export the variables or differences for a container runtime (kubernetes, docker, podman, lxc)",
+            "<format>",
+        );
+        opts.optopt(
+            "",
+            "snapshot",
+            "save a snapshot of all variables to a file",
+            "<file>",
+        );
+        opts.optopt(
+            "",
+            "restore",
+            "restore the variables from a snapshot file",
+            "<file>",
+        );
+        opts.optflag(
+            "",
+            "diff",
+            "display the differences between two sources (a file or \"live\")",
+        );
+        opts.optopt(
+            "",
+            "netns",
+            "use the variables of a network namespace",
+            "<name>",
+        );
+        opts.optopt(
+            "",
+            "pid",
+            "use the variables of the namespaces of a process",
+            "<pid>",
+        );
+        opts.optflag(
+            "",
+            "compare-netns",
+            "display the differences between two network namespaces",
+        );
         opts.optopt(
             "r",
             "pattern",
             "use a regex for matching variable names",
             "<expr>",
         );
+        opts.optopt(
+            "",
+            "query",
+            "use a query for matching the variables (e.g. 'section:net value>1000')",
+            "<query>",
+        );
+        opts.optflag(
+            "",
+            "writable",
+            "display only the variables writable by the current user",
+        );
+        opts.optflag("", "readonly", "display only the read-only variables");
         opts.optflag("q", "quiet", "do not print variable after the value is set");
         opts.optflag("w", "write", "only enable writing a value to variable");
         opts.optflag("o", "", "does nothing");
@@ -97,6 +353,18 @@ impl Args {
             "set the path of the kernel documentation",
             "<path>",
         );
+        opts.optopt(
+            "",
+            "root",
+            "use a directory tree instead of /proc/sys",
+            "<path>",
+        );
+        opts.optopt(
+            "",
+            "from",
+            "read the variables from a dump or sosreport (read-only)",
+            "<path>",
+        );
         opts.optflag("P", "no-pager", "do not pipe output into a pager");
         opts.optflag("v", "verbose", "enable verbose logging");
         opts.optflag("", "tui", "show terminal user interface");
@@ -118,6 +386,20 @@ impl Args {
             .parse(&env_args[1..])
             .map_err(|e| eprintln!("error: `{e}`"))
             .ok()?;
+        let optional_arg_option = OPTIONAL_ARG_OPTIONS
+            .iter()
+            .find(|name| matches.opt_present(name))
+            .copied();
+        let optional_arg = match optional_arg_option {
+            Some(name) if matches.opt_str(name).is_some() => matches.opt_str(name),
+            Some(_) if !matches.free.is_empty() => Some(matches.free.remove(0)),
+            _ => None,
+        };
+        let pattern = matches.opt_str("r").or_else(|| {
+            optional_arg
+                .clone()
+                .filter(|_| matches!(optional_arg_option, Some("history") | Some("watch")))
+        });
 
         let preload_files = matches.opt_present("p") || matches.opt_present("f");
         let show_help = matches.opt_present("h") || matches.opt_present("d");
@@ -131,11 +413,36 @@ impl Args {
             || display_all
             || preload_files
             || matches.opt_present("S")
+            || matches.opt_present("check-drift")
+            || matches.opt_present("confirm")
+            || matches.opt_present("origin")
+            || matches.opt_present("cat-config")
+            || matches.opt_present("history")
+            || matches.opt_present("undo")
+            || matches.opt_present("watch")
+            || matches.opt_present("profile")
+            || matches.opt_present("audit")
+            || matches.opt_present("fleet-report")
+            || matches.opt_present("serve-metrics")
+            || matches.opt_present("metrics-file")
+            || matches.opt_present("export")
+            || matches.opt_present("snapshot")
+            || matches.opt_present("restore")
+            || matches.opt_present("diff")
+            || matches.opt_present("compare-netns")
             || matches.opt_present("r")
+            || matches.opt_present("query")
+            || matches.opt_present("writable")
+            || matches.opt_present("readonly")
             || matches.opt_present("E")
             || matches.opt_present("T")
             || matches.opt_present("J")
             || matches.opt_present("tui");
+        let report_formats = if matches.opt_present("fleet-report") {
+            ["table", "json", "csv"].as_slice()
+        } else {
+            ["table", "json", "junit", "sarif"].as_slice()
+        };
 
         if show_help || env_args.len() == 1 {
             let usage = opts.usage_with_format(|opts| {
@@ -156,6 +463,146 @@ impl Args {
         } else if matches.opt_present("V") {
             println!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
             None
+        } else if matches.opt_present("diff")
+            && !matches.opt_present("profile")
+            && !(1..=2).contains(&matches.free.len())
+        {
+            eprintln!(
+                "{}: --diff requires one or two sources\n\
+                Try `{} --help' for more information.",
+                env!("CARGO_PKG_NAME"),
+                env!("CARGO_PKG_NAME")
+            );
+            None
+        } else if let Some(name) = optional_arg_option.filter(|_| !matches.free.is_empty()) {
+            eprintln!(
+                "{}: unexpected argument for --{}: {}\n\
+                Try `{} --help' for more information.",
+                env!("CARGO_PKG_NAME"),
+                name,
+                matches.free[0],
+                env!("CARGO_PKG_NAME")
+            );
+            None
+        } else if matches.opt_present("compare-netns") && matches.free.len() != 2 {
+            eprintln!(
+                "{}: --compare-netns requires two network namespaces\n\
+                Try `{} --help' for more information.",
+                env!("CARGO_PKG_NAME"),
+                env!("CARGO_PKG_NAME")
+            );
+            None
+        } else if matches.opt_present("root") && matches.opt_present("from") {
+            eprintln!(
+                "{}: --root and --from cannot be used together\n\
+                Try `{} --help' for more information.",
+                env!("CARGO_PKG_NAME"),
+                env!("CARGO_PKG_NAME")
+            );
+            None
+        } else if matches.opt_present("writable") && matches.opt_present("readonly") {
+            eprintln!(
+                "{}: --writable and --readonly cannot be used together\n\
+                Try `{} --help' for more information.",
+                env!("CARGO_PKG_NAME"),
+                env!("CARGO_PKG_NAME")
+            );
+            None
+        } else if matches.opt_present("netns") && matches.opt_present("pid") {
+            eprintln!(
+                "{}: --netns and --pid cannot be used together\n\
+                Try `{} --help' for more information.",
+                env!("CARGO_PKG_NAME"),
+                env!("CARGO_PKG_NAME")
+            );
+            None
+        } else if matches
+            .opt_str("pid")
+            .is_some_and(|value| value.parse::<u32>().is_err())
+        {
+            eprintln!(
+                "{}: invalid process ID\n\
+                Try `{} --help' for more information.",
+                env!("CARGO_PKG_NAME"),
+                env!("CARGO_PKG_NAME")
+            );
+            None
+        } else if ["revert-after", "for"].iter().any(|name| {
+            matches
+                .opt_str(name)
+                .is_some_and(|value| parse_duration(&value).is_none())
+        }) {
+            eprintln!(
+                "{}: invalid duration (e.g. 30, 30s, 10m, 1h)\n\
+                Try `{} --help' for more information.",
+                env!("CARGO_PKG_NAME"),
+                env!("CARGO_PKG_NAME")
+            );
+            None
+        } else if optional_arg_option == Some("undo")
+            && optional_arg
+                .as_ref()
+                .is_some_and(|value| !value.parse::<usize>().is_ok_and(|count| count != 0))
+        {
+            eprintln!(
+                "{}: invalid number of changes to undo\n\
+                Try `{} --help' for more information.",
+                env!("CARGO_PKG_NAME"),
+                env!("CARGO_PKG_NAME")
+            );
+            None
+        } else if matches
+            .opt_str("interval")
+            .is_some_and(|value| !value.parse::<u64>().is_ok_and(|interval| interval != 0))
+        {
+            eprintln!(
+                "{}: invalid interval (in milliseconds)\n\
+                Try `{} --help' for more information.",
+                env!("CARGO_PKG_NAME"),
+                env!("CARGO_PKG_NAME")
+            );
+            None
+        } else if matches
+            .opt_str("report")
+            .is_some_and(|value| !report_formats.contains(&value.to_lowercase().as_str()))
+        {
+            eprintln!(
+                "{}: invalid report format ({})\n\
+                Try `{} --help' for more information.",
+                env!("CARGO_PKG_NAME"),
+                report_formats.join(", "),
+                env!("CARGO_PKG_NAME")
+            );
+            None
+        } else if let Some(Err(e)) = pattern.as_deref().map(Regex::new) {
+            eprintln!(
+                "{}: invalid regex: {}\n\
+                Try `{} --help' for more information.",
+                env!("CARGO_PKG_NAME"),
+                e,
+                env!("CARGO_PKG_NAME")
+            );
+            None
+        } else if let Some(Err(e)) = matches.opt_str("query").map(|v| v.parse::<Query>()) {
+            eprintln!(
+                "{}: invalid query: {}\n\
+                Try `{} --help' for more information.",
+                env!("CARGO_PKG_NAME"),
+                e,
+                env!("CARGO_PKG_NAME")
+            );
+            None
+        } else if matches
+            .opt_str("export")
+            .is_some_and(|value| value.parse::<ExportFormat>().is_err())
+        {
+            eprintln!(
+                "{}: invalid export format (kubernetes, docker, podman, lxc)\n\
+                Try `{} --help' for more information.",
+                env!("CARGO_PKG_NAME"),
+                env!("CARGO_PKG_NAME")
+            );
+            None
         } else if !required_args_present {
             eprintln!(
                 "{}: no variables specified\n\
@@ -192,15 +639,107 @@ impl Args {
                     .opt_str("D")
                     .or_else(|| env::var(KERNEL_DOCS_ENV).ok())
                     .map(PathBuf::from),
+                root: matches.opt_str("root").map(PathBuf::from),
+                from: matches.opt_str("from").map(PathBuf::from),
                 display_type,
                 display_deprecated: matches.opt_present("deprecated"),
                 ignore_errors: matches.opt_present("e"),
                 no_pager: matches.opt_present("P"),
                 preload_files,
                 preload_system_files: matches.opt_present("S"),
-                pattern: matches
-                    .opt_str("r")
-                    .map(|v| Regex::new(&v).expect("invalid regex")),
+                apply_mode: if matches.opt_present("dry-run") {
+                    ApplyMode::DryRun
+                } else if let Some(timeout) = matches
+                    .opt_str("revert-after")
+                    .and_then(|v| parse_duration(&v))
+                {
+                    ApplyMode::RevertAfter(timeout)
+                } else if let Some(duration) =
+                    matches.opt_str("for").and_then(|v| parse_duration(&v))
+                {
+                    ApplyMode::Trial(duration)
+                } else if matches.opt_present("atomic") {
+                    ApplyMode::Atomic
+                } else {
+                    ApplyMode::Default
+                },
+                confirm: matches.opt_present("confirm"),
+                check_drift: matches.opt_present("check-drift"),
+                origin: matches.opt_str("origin"),
+                cat_config: matches.opt_present("cat-config"),
+                save: matches.opt_present("save").then(|| {
+                    PathBuf::from(
+                        matches
+                            .opt_str("save")
+                            .unwrap_or_else(|| DEFAULT_SAVE_PATH.to_string()),
+                    )
+                }),
+                history: matches.opt_present("history"),
+                undo: matches.opt_present("undo").then(|| {
+                    optional_arg
+                        .as_ref()
+                        .and_then(|v| v.parse().ok())
+                        .unwrap_or(1)
+                }),
+                watch: matches.opt_present("watch"),
+                interval: matches
+                    .opt_str("interval")
+                    .and_then(|v| v.parse().ok())
+                    .map(Duration::from_millis),
+                exec: matches.opt_str("exec"),
+                profile: matches.opt_str("profile"),
+                profile_action: if matches.opt_present("apply") {
+                    ProfileAction::Apply
+                } else if matches.opt_present("diff") {
+                    ProfileAction::Diff
+                } else {
+                    ProfileAction::Show
+                },
+                audit: matches.opt_present("audit"),
+                audit_rules: optional_arg
+                    .clone()
+                    .filter(|_| optional_arg_option == Some("audit"))
+                    .map(PathBuf::from),
+                report_type: match matches.opt_str("report") {
+                    Some(report) => ReportType::from(report.as_str()),
+                    None if output_type == OutputType::Json => ReportType::Json,
+                    None => ReportType::Default,
+                },
+                fleet_report: matches.opt_str("fleet-report").map(PathBuf::from),
+                include_volatile: matches.opt_present("include-volatile"),
+                serve_metrics: matches.opt_str("serve-metrics"),
+                metrics_file: matches.opt_str("metrics-file").map(PathBuf::from),
+                export: matches.opt_str("export").and_then(|v| v.parse().ok()),
+                snapshot: matches.opt_str("snapshot").map(PathBuf::from),
+                restore: matches.opt_str("restore").map(PathBuf::from),
+                diff: (matches.opt_present("diff") && !matches.opt_present("profile")).then(|| {
+                    let new_source = matches.free.get(1).map(|v| Source::from(v.as_str()));
+                    (
+                        Source::from(matches.free[0].as_str()),
+                        new_source.unwrap_or(Source::Live),
+                    )
+                }),
+                target_namespace: matches
+                    .opt_str("netns")
+                    .map(NamespaceTarget::Netns)
+                    .or_else(|| {
+                        matches
+                            .opt_str("pid")
+                            .and_then(|v| v.parse().ok())
+                            .map(NamespaceTarget::Pid)
+                    }),
+                compare_netns: matches
+                    .opt_present("compare-netns")
+                    .then(|| (matches.free[0].to_string(), matches.free[1].to_string())),
+                pattern: pattern.and_then(|v| Regex::new(&v).ok()),
+                access_filter: if matches.opt_present("writable") {
+                    Some(AccessFilter::Writable)
+                } else if matches.opt_present("readonly") {
+                    Some(AccessFilter::ReadOnly)
+                } else {
+                    None
+                },
+                query: matches.opt_str("query").and_then(|v| v.parse().ok()),
                 explain: matches.opt_present("E"),
                 output_type,
                 show_tui: matches.opt_present("tui"),
@@ -245,6 +784,255 @@ mod tests {
             .values
             .is_empty());
 
+        assert_eq!(Some(Duration::from_secs(30)), parse_duration("30"));
+        assert_eq!(Some(Duration::from_secs(600)), parse_duration("10m"));
+        assert_eq!(Some(Duration::from_secs(3600)), parse_duration("1h"));
+        assert_eq!(None, parse_duration("10d"));
+        assert_eq!(None, parse_duration("99999999999999999h"));
+        assert_eq!(None, parse_duration("18446744073709551615"));
+        assert!(Args::parse(vec![
+            String::new(),
+            String::from("--revert-after"),
+            String::from("18446744073709551615"),
+            String::from("vm.swappiness=10"),
+        ])
+        .is_none());
+        assert_eq!(
+            ApplyMode::Trial(Duration::from_secs(5)),
+            Args::parse(vec![
+                String::new(),
+                String::from("--for"),
+                String::from("5s"),
+                String::from("vm.swappiness=10"),
+            ])
+            .expect("failed to parse arguments")
+            .apply_mode
+        );
+        assert!(Args::parse(vec![
+            String::new(),
+            String::from("--revert-after"),
+            String::from("x"),
+            String::from("vm.swappiness=10"),
+        ])
+        .is_none());
+
+        assert_eq!(
+            Some(1),
+            Args::parse(vec![String::new(), String::from("--undo")])
+                .expect("failed to parse arguments")
+                .undo
+        );
+        assert_eq!(
+            Some(3),
+            Args::parse(vec![String::new(), String::from("--undo=3")])
+                .expect("failed to parse arguments")
+                .undo
+        );
+        assert!(Args::parse(vec![String::new(), String::from("--undo=0")]).is_none());
+        assert_eq!(
+            Some(3),
+            Args::parse(vec![
+                String::new(),
+                String::from("--undo"),
+                String::from("3")
+            ])
+            .expect("failed to parse arguments")
+            .undo
+        );
+        assert!(Args::parse(vec![
+            String::new(),
+            String::from("--undo"),
+            String::from("3"),
+            String::from("4"),
+        ])
+        .is_none());
+        let args = Args::parse(vec![
+            String::new(),
+            String::from("--history"),
+            String::from("swap"),
+        ])
+        .expect("failed to parse arguments");
+        assert!(args.history);
+        assert!(args.values.is_empty());
+        assert_eq!(
+            Some("swap"),
+            args.pattern.as_ref().map(|pattern| pattern.as_str())
+        );
+        for arg in ["--history", "--watch", "-r"] {
+            assert!(Args::parse(vec![
+                String::new(),
+                String::from(arg),
+                String::from("swap(")
+            ])
+            .is_none());
+        }
+
+        let args = Args::parse(vec![
+            String::new(),
+            String::from("--watch=^net\\."),
+            String::from("--interval"),
+            String::from("500"),
+        ])
+        .expect("failed to parse arguments");
+        assert!(args.watch);
+        assert_eq!(Some(Duration::from_millis(500)), args.interval);
+        assert_eq!(
+            Some("^net\\."),
+            args.pattern.as_ref().map(|pattern| pattern.as_str())
+        );
+
+        let args = Args::parse(vec![
+            String::new(),
+            String::from("--watch"),
+            String::from("tcp_.*mem"),
+        ])
+        .expect("failed to parse arguments");
+        assert!(args.values.is_empty());
+        assert_eq!(
+            Some("tcp_.*mem"),
+            args.pattern.as_ref().map(|pattern| pattern.as_str())
+        );
+
+        let args = Args::parse(vec![
+            String::new(),
+            String::from("--audit=rules.conf"),
+            String::from("--report"),
+            String::from("sarif"),
+        ])
+        .expect("failed to parse arguments");
+        assert!(args.audit);
+        assert_eq!(Some(PathBuf::from("rules.conf")), args.audit_rules);
+        assert_eq!(
+            Some(PathBuf::from("/tmp/rules.conf")),
+            Args::parse(vec![
+                String::new(),
+                String::from("--audit"),
+                String::from("/tmp/rules.conf"),
+            ])
+            .expect("failed to parse arguments")
+            .audit_rules
+        );
+        assert!(Args::parse(vec![
+            String::new(),
+            String::from("--audit"),
+            String::from("rules.conf"),
+            String::from("vm.swappiness"),
+        ])
+        .is_none());
+        assert_eq!(ReportType::Sarif, args.report_type);
+        assert_eq!(
+            ReportType::Json,
+            Args::parse(vec![
+                String::new(),
+                String::from("--audit"),
+                String::from("-J")
+            ])
+            .expect("failed to parse arguments")
+            .report_type
+        );
+        assert!(Args::parse(vec![
+            String::new(),
+            String::from("--audit"),
+            String::from("--report"),
+            String::from("xml"),
+        ])
+        .is_none());
+        let args = Args::parse(vec![
+            String::new(),
+            String::from("--fleet-report"),
+            String::from("dumps"),
+            String::from("--report"),
+            String::from("csv"),
+            String::from("--include-volatile"),
+        ])
+        .expect("failed to parse arguments");
+        assert_eq!(Some(PathBuf::from("dumps")), args.fleet_report);
+        assert_eq!(ReportType::Csv, args.report_type);
+        assert!(args.include_volatile);
+        assert!(Args::parse(vec![
+            String::new(),
+            String::from("--fleet-report"),
+            String::from("dumps"),
+            String::from("--report"),
+            String::from("junit"),
+        ])
+        .is_none());
+
+        assert_eq!(
+            Some(PathBuf::from("sysctl.txt")),
+            Args::parse(vec![
+                String::new(),
+                String::from("--from"),
+                String::from("sysctl.txt"),
+                String::from("-a"),
+            ])
+            .expect("failed to parse arguments")
+            .from
+        );
+        assert!(Args::parse(vec![
+            String::new(),
+            String::from("--from"),
+            String::from("sysctl.txt"),
+            String::from("--root"),
+            String::from("/mnt/proc/sys"),
+            String::from("-a"),
+        ])
+        .is_none());
+        assert!(Args::parse(vec![
+            String::new(),
+            String::from("--writable"),
+            String::from("--readonly"),
+        ])
+        .is_none());
+
+        let args = Args::parse(vec![
+            String::new(),
+            String::from("--pid"),
+            String::from("1"),
+            String::from("-a"),
+        ])
+        .expect("failed to parse arguments");
+        assert_eq!(Some(NamespaceTarget::Pid(1)), args.target_namespace);
+        assert_eq!(
+            Some((String::from("blue"), String::from("red"))),
+            Args::parse(vec![
+                String::new(),
+                String::from("--compare-netns"),
+                String::from("blue"),
+                String::from("red"),
+            ])
+            .expect("failed to parse arguments")
+            .compare_netns
+        );
+        assert!(Args::parse(vec![
+            String::new(),
+            String::from("--compare-netns"),
+            String::from("blue"),
+        ])
+        .is_none());
+        assert!(Args::parse(vec![
+            String::new(),
+            String::from("--pid"),
+            String::from("x"),
+            String::from("-a"),
+        ])
+        .is_none());
+
+        assert!(Args::parse(vec![
+            String::new(),
+            String::from("--query"),
+            String::from("section:net value>1000"),
+        ])
+        .expect("failed to parse arguments")
+        .query
+        .is_some());
+        assert!(Args::parse(vec![
+            String::new(),
+            String::from("--query"),
+            String::from("(section:net"),
+        ])
+        .is_none());
+
         assert_eq!(
             DisplayType::Binary,
             Args::parse(vec![String::new(), String::from("-A"), String::from("-b")])
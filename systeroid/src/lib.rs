@@ -7,20 +7,25 @@ pub mod app;
 /// Command-line argument parser.
 pub mod args;
 
-use crate::app::App;
+use crate::app::{App, ApplyMode, ProfileAction, DEFAULT_WATCH_INTERVAL};
 use crate::args::Args;
 use std::io::Write;
 use std::path::PathBuf;
+use std::sync::Arc;
 use systeroid_core::cache::Cache;
 use systeroid_core::config::Config;
 use systeroid_core::error::Result;
+use systeroid_core::sysctl::backend::{DirectoryBackend, OfflineBackend};
 use systeroid_core::sysctl::controller::Sysctl;
+use systeroid_core::sysctl::journal::Journal;
+use systeroid_core::sysctl::query::{Field, Query};
 
 /// Runs `systeroid`.
 pub fn run<Output: Write>(args: Args, output: &mut Output) -> Result<()> {
     let mut config = Config {
         display_deprecated: args.display_deprecated,
         kernel_docs: args.kernel_docs,
+        target_namespace: args.target_namespace,
         ..Default::default()
     };
     config.cli.ignore_errors = args.ignore_errors;
@@ -29,29 +34,110 @@ pub fn run<Output: Write>(args: Args, output: &mut Output) -> Result<()> {
     config.cli.display_type = args.display_type;
     config.cli.output_type = args.output_type;
     config.parse(args.config)?;
+    if args.root.is_some() || args.from.is_some() || config.target_namespace.is_some() {
+        // journal entries are undone on the host, so the other targets are not recorded
+        config.journal_path = None;
+    } else if config.journal_path.is_none() {
+        config.journal_path = Journal::get_default_path();
+    }
     log::trace!("{:?}", config);
-    let mut sysctl = Sysctl::init(config)?;
-    if args.explain {
+    let mut sysctl = match (args.root, args.from) {
+        (_, Some(path)) => {
+            Sysctl::init_with_backend(config, Arc::new(OfflineBackend::load(path)?))?
+        }
+        (Some(root), None) => {
+            Sysctl::init_with_backend(config, Arc::new(DirectoryBackend::new(root)))?
+        }
+        (None, None) => Sysctl::init(config)?,
+    };
+    let query = [
+        args.pattern.clone().map(Query::from),
+        args.access_filter.map(Query::from),
+        args.query,
+    ]
+    .into_iter()
+    .flatten()
+    .fold(Query::All, Query::and);
+    if args.explain || query.uses(Field::Doc) {
         sysctl.update_docs_from_cache(&Cache::init()?)?;
     }
     let mut app = App::new(&mut sysctl, output);
 
-    if args.preload_system_files {
-        app.preload_from_system()?;
+    if let Some(path) = args.snapshot {
+        app.save_snapshot(path)?;
+    } else if let Some(path) = args.restore {
+        app.apply(args.apply_mode, |app| app.restore_snapshot(path))?;
+    } else if let Some(name) = args.profile {
+        match args.profile_action {
+            ProfileAction::Show => app.display_profile(&name)?,
+            ProfileAction::Diff => app.display_profile_diff(&name, args.pattern)?,
+            ProfileAction::Apply => app.apply(args.apply_mode, |app| app.apply_profile(&name))?,
+        }
+    } else if let Some((old_source, new_source)) = args.diff {
+        match args.export {
+            Some(format) => app.export_diff(old_source, new_source, args.pattern, format)?,
+            None => app.display_diff(old_source, new_source, args.pattern)?,
+        }
+    } else if let Some((old_netns, new_netns)) = args.compare_netns {
+        app.display_netns_diff(&old_netns, &new_netns, args.pattern)?;
+    } else if args.confirm {
+        app.confirm_pending()?;
+    } else if args.history {
+        app.display_history(args.pattern)?;
+    } else if let Some(count) = args.undo {
+        app.apply(args.apply_mode, |app| app.undo(count))?;
+    } else if args.watch {
+        app.watch(
+            args.pattern,
+            args.interval.unwrap_or(DEFAULT_WATCH_INTERVAL),
+            args.exec,
+        )?;
+    } else if let Some(param_name) = args.origin {
+        app.display_origin(&param_name)?;
+    } else if args.cat_config {
+        app.display_system_files()?;
+    } else if args.check_drift {
+        app.check_drift()?;
+    } else if args.audit {
+        app.audit(args.audit_rules, args.report_type)?;
+    } else if let Some(dir) = args.fleet_report {
+        app.display_fleet_report(dir, args.pattern, args.report_type, args.include_volatile)?;
+    } else if let Some(address) = args.serve_metrics {
+        app.serve_metrics(&address, &query)?;
+    } else if let Some(path) = args.metrics_file {
+        app.save_metrics(path, &query)?;
+    } else if let Some(format) = args.export {
+        app.export_parameters(format, args.values, &query)?;
+    } else if args.preload_system_files {
+        app.apply(args.apply_mode, |app| app.preload_from_system())?;
     } else if args.values.is_empty() {
-        app.display_parameters(args.pattern, args.explain)?;
+        app.display_parameters(&query, args.explain)?;
     } else if args.explain {
         for param in args.values {
             app.display_documentation(&param)?;
         }
     } else if args.preload_files {
-        for file in args.values {
-            app.preload_from_file(PathBuf::from(file))?;
-        }
+        app.apply(args.apply_mode, |app| {
+            args.values
+                .into_iter()
+                .try_for_each(|file| app.preload_from_file(PathBuf::from(file)))
+        })?;
+    } else if let Some(save_path) = args.save {
+        let apply_mode = match args.apply_mode {
+            ApplyMode::RevertAfter(_) | ApplyMode::Trial(_) => ApplyMode::Default,
+            apply_mode => apply_mode,
+        };
+        app.apply(apply_mode, |app| {
+            args.values
+                .into_iter()
+                .try_for_each(|param| app.save_parameter(param, save_path.clone()))
+        })?;
     } else {
-        for param in args.values {
-            app.process_parameter(param, true, args.write)?;
-        }
+        app.apply(args.apply_mode, |app| {
+            args.values
+                .into_iter()
+                .try_for_each(|param| app.process_parameter(param, true, args.write))
+        })?;
     }
 
     Ok(())
@@ -10,6 +10,8 @@ fn main() {
         let mut builder = LoggerBuilder::from_default_env();
         if args.verbose {
             builder.filter(None, LevelFilter::Trace);
+        } else if env::var("RUST_LOG").is_err() {
+            builder.filter(None, LevelFilter::Warn);
         }
         builder
             .format(|buf, record| writeln!(buf, "{}", record.args()))
@@ -23,6 +25,9 @@ fn main() {
             if let Some(kernel_docs) = args.kernel_docs {
                 command.arg("--docs").arg(kernel_docs);
             }
+            if let Some(root) = args.root {
+                command.arg("--root").arg(root);
+            }
             match command.spawn().map(|mut child| child.wait()) {
                 Ok(_) => process::exit(0),
                 Err(e) => {
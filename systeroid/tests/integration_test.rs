@@ -0,0 +1,453 @@
+use parseit::regex::Regex;
+use std::fs::{self, Permissions};
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::time::Duration;
+use systeroid::app::{ApplyMode, ProfileAction};
+use systeroid::args::Args;
+use systeroid_core::error::Result;
+use systeroid_core::sysctl::access::AccessFilter;
+use systeroid_core::sysctl::export::ExportFormat;
+use systeroid_core::sysctl::r#type::{DisplayType, OutputType, ReportType};
+use systeroid_core::sysctl::source::Source;
+
+/// Creates a directory tree that is shaped like `/proc/sys`.
+fn create_fixture_tree(root: &Path) -> Result<()> {
+    for (path, value) in [
+        ("kernel/hostname", "localhost"),
+        ("kernel/ostype", "Linux"),
+        ("vm/swappiness", "60"),
+        ("net/ipv4/tcp_rmem", "4096\t131072\t6291456"),
+    ] {
+        let path = root.join(path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, format!("{value}\n"))?;
+    }
+    fs::set_permissions(root.join("kernel/ostype"), Permissions::from_mode(0o444))?;
+    Ok(())
+}
+
+#[test]
+fn test_systeroid_with_root() -> Result<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let root = temp_dir.path().join("sys");
+    create_fixture_tree(&root)?;
+
+    let args = Args {
+        root: Some(root.clone()),
+        ..Args::default()
+    };
+    let mut output = Vec::new();
+    systeroid::run(args, &mut output)?;
+    assert_eq!(
+        concat!(
+            "kernel.hostname = localhost\n",
+            "kernel.ostype = Linux\n",
+            "net.ipv4.tcp_rmem = 4096\t131072\t6291456\n",
+            "vm.swappiness = 60\n",
+        ),
+        String::from_utf8_lossy(&output)
+    );
+
+    let args = Args {
+        root: Some(root.clone()),
+        values: vec![String::from("vm.swappiness=10")],
+        ..Args::default()
+    };
+    let mut output = Vec::new();
+    systeroid::run(args, &mut output)?;
+    assert_eq!("vm.swappiness = 10\n", String::from_utf8_lossy(&output));
+    assert_eq!("10\n", fs::read_to_string(root.join("vm/swappiness"))?);
+
+    for value in ["on", "localhost"] {
+        let args = Args {
+            root: Some(root.clone()),
+            values: vec![format!("kernel.hostname={value}")],
+            ..Args::default()
+        };
+        let mut output = Vec::new();
+        systeroid::run(args, &mut output)?;
+        assert_eq!(
+            format!("kernel.hostname = {value}\n"),
+            String::from_utf8_lossy(&output)
+        );
+        assert_eq!(
+            format!("{value}\n"),
+            fs::read_to_string(root.join("kernel/hostname"))?
+        );
+    }
+
+    let args = Args {
+        root: Some(root.clone()),
+        values: vec![String::from("vm.swappiness=300")],
+        ..Args::default()
+    };
+    assert_eq!(
+        "validation error: `vm.swappiness: 300 is greater than the maximum value (200)`",
+        systeroid::run(args, &mut Vec::new())
+            .expect_err("invalid value")
+            .to_string()
+    );
+    assert_eq!("10\n", fs::read_to_string(root.join("vm/swappiness"))?);
+
+    let args = Args {
+        root: Some(root.clone()),
+        values: vec![String::from("kernel"), String::from("net")],
+        output_type: OutputType::Json,
+        ..Args::default()
+    };
+    let mut output = Vec::new();
+    systeroid::run(args, &mut output)?;
+    let output = String::from_utf8_lossy(&output);
+    assert!(output.contains("\"name\":\"kernel.ostype\""));
+    assert!(output.contains("\"typed_value\":[4096,131072,6291456]"));
+    assert!(output.contains("\"access\":{\"mode\":\"0444\""));
+
+    let args = Args {
+        root: Some(root.clone()),
+        access_filter: Some(AccessFilter::ReadOnly),
+        display_type: DisplayType::Name,
+        ..Args::default()
+    };
+    let mut output = Vec::new();
+    systeroid::run(args, &mut output)?;
+    assert_eq!("kernel.ostype\n", String::from_utf8_lossy(&output));
+
+    let snapshot = root.with_extension("json");
+    let args = Args {
+        root: Some(root.clone()),
+        snapshot: Some(snapshot.clone()),
+        quiet: true,
+        ..Args::default()
+    };
+    systeroid::run(args, &mut Vec::new())?;
+    fs::write(root.join("vm/swappiness"), "30\n")?;
+    fs::write(root.join("kernel/hostname"), "example\n")?;
+    let args = Args {
+        root: Some(root.clone()),
+        restore: Some(snapshot.clone()),
+        apply_mode: ApplyMode::DryRun,
+        ..Args::default()
+    };
+    let mut output = Vec::new();
+    systeroid::run(args, &mut output)?;
+    assert_eq!(
+        "~ kernel.hostname = example -> localhost\n~ vm.swappiness = 30 -> 10\n",
+        String::from_utf8_lossy(&output)
+    );
+    assert_eq!("30\n", fs::read_to_string(root.join("vm/swappiness"))?);
+    let invalid_snapshot = root.with_extension("invalid.json");
+    fs::write(
+        &invalid_snapshot,
+        fs::read_to_string(&snapshot)?.replace("\"10\"", "\"300\""),
+    )?;
+    let args = Args {
+        root: Some(root.clone()),
+        restore: Some(invalid_snapshot),
+        apply_mode: ApplyMode::Atomic,
+        quiet: true,
+        ..Args::default()
+    };
+    assert!(systeroid::run(args, &mut Vec::new()).is_err());
+    assert_eq!(
+        "example\n",
+        fs::read_to_string(root.join("kernel/hostname"))?
+    );
+    assert_eq!("30\n", fs::read_to_string(root.join("vm/swappiness"))?);
+    let args = Args {
+        root: Some(root.clone()),
+        restore: Some(snapshot.clone()),
+        ..Args::default()
+    };
+    let mut output = Vec::new();
+    systeroid::run(args, &mut output)?;
+    assert_eq!(
+        "kernel.hostname = localhost\nvm.swappiness = 10\n",
+        String::from_utf8_lossy(&output)
+    );
+    assert_eq!("10\n", fs::read_to_string(root.join("vm/swappiness"))?);
+
+    let config = root.with_extension("conf");
+    fs::write(&config, "vm.swappiness = 20\nvm.unknown = 1\n")?;
+    let args = Args {
+        root: Some(root.clone()),
+        diff: Some((Source::File(config.clone()), Source::Live)),
+        pattern: Regex::new("^vm").ok(),
+        ..Args::default()
+    };
+    let mut output = Vec::new();
+    systeroid::run(args, &mut output)?;
+    assert_eq!(
+        "~ vm.swappiness = 20 -> 10\n- vm.unknown = 1\n",
+        String::from_utf8_lossy(&output)
+    );
+
+    let save_path = root.with_extension("d").join("90-systeroid.conf");
+    let args = Args {
+        root: Some(root.clone()),
+        save: Some(save_path.clone()),
+        values: vec![String::from("vm.swappiness=40")],
+        ..Args::default()
+    };
+    let mut output = Vec::new();
+    systeroid::run(args, &mut output)?;
+    assert_eq!("vm.swappiness = 40\n", String::from_utf8_lossy(&output));
+    assert_eq!("40\n", fs::read_to_string(root.join("vm/swappiness"))?);
+    let contents = fs::read_to_string(&save_path)?;
+    assert!(contents.starts_with("# This file is managed by systeroid."));
+    assert!(contents.ends_with("\nvm.swappiness = 40\n"));
+
+    fs::remove_dir_all(root.with_extension("d"))?;
+
+    fs::write(
+        &config,
+        "kernel.hostname = example\nvm.swappiness = 50\nvm.swappiness = 300\n",
+    )?;
+    let args = Args {
+        root: Some(root.clone()),
+        preload_files: true,
+        apply_mode: ApplyMode::Atomic,
+        quiet: true,
+        values: vec![config.to_string_lossy().to_string()],
+        ..Args::default()
+    };
+    let mut output = Vec::new();
+    assert_eq!(
+        "transaction error: `2 change(s) rolled back, 0 failed`",
+        systeroid::run(args, &mut output)
+            .expect_err("invalid value")
+            .to_string()
+    );
+    assert_eq!(
+        "vm.swappiness = 40 (rolled back from 50)\n\
+        kernel.hostname = localhost (rolled back from example)\n",
+        String::from_utf8_lossy(&output)
+    );
+    assert_eq!("40\n", fs::read_to_string(root.join("vm/swappiness"))?);
+    assert_eq!(
+        "localhost\n",
+        fs::read_to_string(root.join("kernel/hostname"))?
+    );
+    fs::write(
+        &config,
+        "kernel.ostype = BSD\nvm.swappiness = 40\nvm.swappiness = 50\nvm.unknown = 1\n",
+    )?;
+    let args = Args {
+        root: Some(root.clone()),
+        preload_files: true,
+        apply_mode: ApplyMode::DryRun,
+        values: vec![config.to_string_lossy().to_string()],
+        ..Args::default()
+    };
+    let mut output = Vec::new();
+    assert_eq!(
+        "dry run error: `2 value(s) cannot be set`",
+        systeroid::run(args, &mut output)
+            .expect_err("invalid values")
+            .to_string()
+    );
+    assert_eq!(
+        concat!(
+            "! kernel.ostype = Linux -> BSD (read-only)\n",
+            "= vm.swappiness = 40\n",
+            "~ vm.swappiness = 40 -> 50\n",
+            "! vm.unknown = 1 (unknown parameter)\n",
+        ),
+        String::from_utf8_lossy(&output)
+    );
+    assert_eq!("40\n", fs::read_to_string(root.join("vm/swappiness"))?);
+
+    let args = Args {
+        root: Some(root.clone()),
+        apply_mode: ApplyMode::Trial(Duration::from_millis(200)),
+        values: vec![String::from("vm.swappiness=70")],
+        ..Args::default()
+    };
+    let mut output = Vec::new();
+    systeroid::run(args, &mut output)?;
+    assert_eq!(
+        "vm.swappiness = 70\nvm.swappiness = 40 (rolled back from 70)\n",
+        String::from_utf8_lossy(&output)
+    );
+    assert_eq!("40\n", fs::read_to_string(root.join("vm/swappiness"))?);
+
+    let journal = root.with_extension("jsonl");
+    let journal_config = root.with_extension("ini");
+    fs::write(
+        &journal_config,
+        format!("[general]\njournal_path = {}\n", journal.display()),
+    )?;
+    let args = Args {
+        root: Some(root.clone()),
+        config: Some(journal_config.clone()),
+        values: vec![
+            String::from("vm.swappiness=10"),
+            String::from("kernel.hostname=example"),
+        ],
+        ..Args::default()
+    };
+    systeroid::run(args, &mut Vec::new())?;
+    assert_eq!("10\n", fs::read_to_string(root.join("vm/swappiness"))?);
+    assert!(!journal.exists());
+    let args = Args {
+        root: Some(root.clone()),
+        config: Some(journal_config.clone()),
+        undo: Some(2),
+        ..Args::default()
+    };
+    assert_eq!(
+        "journal error: `journal path is not set`",
+        systeroid::run(args, &mut Vec::new())
+            .expect_err("journal is set")
+            .to_string()
+    );
+    let args = Args {
+        root: Some(root.clone()),
+        values: vec![
+            String::from("vm.swappiness=40"),
+            String::from("kernel.hostname=localhost"),
+        ],
+        ..Args::default()
+    };
+    systeroid::run(args, &mut Vec::new())?;
+
+    let profile_dir = root.with_extension("profiles");
+    fs::create_dir_all(&profile_dir)?;
+    fs::write(
+        profile_dir.join("base.conf"),
+        "# description: Base\nkernel.hostname = base\n",
+    )?;
+    fs::write(
+        profile_dir.join("desktop.conf"),
+        "# include: base\nvm.swappiness = 10\n",
+    )?;
+    let profile_config = root.with_extension("cfg");
+    fs::write(
+        &profile_config,
+        format!("[general]\nprofile_dir = {}\n", profile_dir.display()),
+    )?;
+    let args = Args {
+        root: Some(root.clone()),
+        config: Some(profile_config.clone()),
+        profile: Some(String::from("desktop")),
+        profile_action: ProfileAction::Diff,
+        ..Args::default()
+    };
+    let mut output = Vec::new();
+    systeroid::run(args, &mut output)?;
+    assert_eq!(
+        "~ kernel.hostname = localhost -> base\n~ vm.swappiness = 40 -> 10\n",
+        String::from_utf8_lossy(&output)
+    );
+    let args = Args {
+        root: Some(root.clone()),
+        config: Some(profile_config.clone()),
+        profile: Some(String::from("desktop")),
+        profile_action: ProfileAction::Apply,
+        ..Args::default()
+    };
+    let mut output = Vec::new();
+    systeroid::run(args, &mut output)?;
+    assert_eq!(
+        "kernel.hostname = base\nvm.swappiness = 10\n",
+        String::from_utf8_lossy(&output)
+    );
+    assert_eq!("base\n", fs::read_to_string(root.join("kernel/hostname"))?);
+
+    let rules = root.with_extension("rules");
+    fs::write(
+        &rules,
+        "[swappiness]\ncheck = \"vm.swappiness <= 10\"\n\n\
+        [hostname]\ncheck = \"kernel.hostname != base\"\nseverity = low\n",
+    )?;
+    let args = Args {
+        root: Some(root.clone()),
+        audit: true,
+        audit_rules: Some(rules.clone()),
+        report_type: ReportType::Junit,
+        ..Args::default()
+    };
+    let mut output = Vec::new();
+    assert!(systeroid::run(args, &mut output).is_err());
+    let output = String::from_utf8_lossy(&output);
+    assert!(output.contains("tests=\"2\" failures=\"1\" skipped=\"0\""));
+    assert!(output.contains("<testcase name=\"swappiness\" classname=\"vm.swappiness\"/>"));
+
+    let metrics = root.with_extension("prom");
+    let args = Args {
+        root: Some(root.clone()),
+        metrics_file: Some(metrics.clone()),
+        pattern: Regex::new("swappiness").ok(),
+        ..Args::default()
+    };
+    systeroid::run(args, &mut Vec::new())?;
+    assert!(fs::read_to_string(&metrics)?
+        .ends_with("\nsysteroid_param{name=\"vm.swappiness\",section=\"vm\"} 10\n"));
+
+    let args = Args {
+        root: Some(root.clone()),
+        export: Some(ExportFormat::Lxc),
+        values: vec![
+            String::from("vm.swappiness"),
+            String::from("net.core.somaxconn=1024"),
+        ],
+        ..Args::default()
+    };
+    let mut output = Vec::new();
+    systeroid::run(args, &mut output)?;
+    assert_eq!(
+        "lxc.sysctl.net.core.somaxconn = 1024\n",
+        String::from_utf8_lossy(&output)
+    );
+
+    let args = Args {
+        from: Some(snapshot.clone()),
+        values: vec![String::from("vm")],
+        ..Args::default()
+    };
+    let mut output = Vec::new();
+    systeroid::run(args, &mut output)?;
+    assert_eq!("vm.swappiness = 10\n", String::from_utf8_lossy(&output));
+    let args = Args {
+        from: Some(root.clone()),
+        values: vec![String::from("vm.swappiness=20")],
+        ..Args::default()
+    };
+    assert!(systeroid::run(args, &mut Vec::new()).is_err());
+    assert_eq!("10\n", fs::read_to_string(root.join("vm/swappiness"))?);
+
+    let fleet = root.with_extension("fleet");
+    fs::create_dir_all(&fleet)?;
+    fs::copy(&snapshot, fleet.join("node-1.json"))?;
+    fs::write(fleet.join("node-2.txt"), "vm.swappiness = 10\n")?;
+    fs::write(fleet.join("node-3.txt"), "vm.swappiness = 60\n")?;
+    let args = Args {
+        fleet_report: Some(fleet.clone()),
+        report_type: ReportType::Csv,
+        pattern: Regex::new("swappiness").ok(),
+        ..Args::default()
+    };
+    let mut output = Vec::new();
+    systeroid::run(args, &mut output)?;
+    assert_eq!(
+        "name,majority,node-1,node-2,node-3\nvm.swappiness,10,10,10,60\n",
+        String::from_utf8_lossy(&output)
+    );
+
+    let args = Args {
+        root: Some(root.clone()),
+        watch: true,
+        pattern: Regex::new("tcp_wmem").ok(),
+        ..Args::default()
+    };
+    assert_eq!(
+        "watch error: `no parameters found matching \"tcp_wmem\"`",
+        systeroid::run(args, &mut Vec::new())
+            .expect_err("no matching parameters")
+            .to_string()
+    );
+
+    Ok(())
+}